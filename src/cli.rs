@@ -1,5 +1,9 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use yewpb::config::ConfigFormat;
+use yewpb::rename::NameCase;
+use yewpb::Layout;
 
 #[derive(Parser)]
 #[command(
@@ -21,6 +25,7 @@ pub enum Commands {
     },
     /// 将已保存的远程仓库应用到当前 git 仓库
     Apply {
+        #[arg(conflicts_with = "recursive")]
         repo: Option<String>,
         /// 自动确认推断的仓库名称
         #[arg(short = 'y', long = "yes")]
@@ -34,12 +39,52 @@ pub enum Commands {
         /// 不安装 pre-push hook
         #[arg(long = "no-hook")]
         no_hook: bool,
+        /// 仅应用指定分组中的远程仓库（默认使用配置中的 defaults.group）
+        #[arg(short = 'g', long = "group")]
+        group: Option<String>,
+        /// 远程仓库布局：unified 为单个 yewpb 远程仓库，split 为每个平台一个同名远程仓库，both 同时创建
+        #[arg(long = "layout", value_enum, default_value = "unified")]
+        layout: Layout,
+        /// 同时删除 yewpb 远程仓库中不属于任何配置的推送地址（默认保留手动添加的地址）
+        #[arg(long = "prune")]
+        prune: bool,
+        /// 同时检查可用性的地址数量上限（默认 8）
+        #[arg(short = 'j', long = "jobs", value_name = "N")]
+        jobs: Option<NonZeroUsize>,
+        /// 应用到目录下找到的所有 git 仓库，仓库名称按 origin 地址或目录名推断
+        #[arg(short = 'r', long = "recursive", value_name = "DIR")]
+        recursive: Option<PathBuf>,
+        /// 只处理匹配该 glob 的仓库（可多次使用），不含 '/' 时匹配目录名，否则匹配相对路径
+        #[arg(long = "include", value_name = "GLOB", requires = "recursive")]
+        include: Vec<String>,
+        /// 跳过匹配该 glob 的目录与仓库（可多次使用）
+        #[arg(long = "exclude", value_name = "GLOB", requires = "recursive")]
+        exclude: Vec<String>,
+        /// 向下查找仓库的最大目录层数
+        #[arg(
+            long = "max-depth",
+            value_name = "N",
+            default_value_t = 3,
+            requires = "recursive"
+        )]
+        max_depth: usize,
     },
     /// 清理本工具创建的远程仓库
     Clean {
         /// 仅显示将要执行的操作，不实际修改
         #[arg(short = 'd', long = "dry-run")]
         dry_run: bool,
+        /// 按最早的快照恢复远程仓库，回到使用 yewpb 之前的状态
+        #[arg(long = "restore")]
+        restore: bool,
+    },
+    /// 按 apply/clean 前保存的快照恢复远程仓库
+    Restore {
+        /// 快照编号，可以只写唯一的前缀，默认恢复最近一次快照
+        snapshot: Option<String>,
+        /// 列出所有快照
+        #[arg(short = 'l', long = "list", conflicts_with = "snapshot")]
+        list: bool,
     },
     /// 推送当前分支到所有已配置的远程仓库
     Push {
@@ -51,6 +96,9 @@ pub enum Commands {
         /// 排除指定名称的仓库（可多次使用）
         #[arg(long = "except")]
         except: Vec<String>,
+        /// 仅推送到指定分组中的仓库（默认使用配置中的 defaults.group）
+        #[arg(short = 'g', long = "group")]
+        group: Option<String>,
         /// 强制推送（覆盖远程历史）
         #[arg(short = 'f', long = "force")]
         force: bool,
@@ -86,6 +134,9 @@ pub enum Commands {
         /// 连接检查超时时间（秒）
         #[arg(long = "timeout")]
         timeout: Option<u64>,
+        /// 同时检查的远程仓库数量上限（默认 8）
+        #[arg(short = 'j', long = "jobs", value_name = "N")]
+        jobs: Option<NonZeroUsize>,
     },
     /// 管理 git alias，将本工具注册为 git 子命令
     Alias {
@@ -110,18 +161,51 @@ pub enum Commands {
 }
 
 /// 配置相关的子命令
+// 命令行参数只解析一次，不必为变体大小差异引入装箱
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum ConfigCommands {
     /// 添加或更新远程仓库配置
     Set {
         name: String,
-        base: String,
+        /// 基础地址，生成时拼接为 {base}/{repo}.git（更新已有配置时可省略）
+        base: Option<String>,
+        /// URL 模板，支持 {repo}、{owner}、{group}、{dir}、{branch_prefix}，字面量花括号写作 {{ 和 }}
+        #[arg(short = 't', long = "template")]
+        template: Option<String>,
+        /// 模板变量，格式为 KEY=VALUE（可多次使用）
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+        #[command(flatten)]
+        rename: RenameArgs,
+        #[command(flatten)]
+        policy: PolicyArgs,
         /// 可选的备注信息，仅作为提示
         #[arg(short = 'n', long = "note")]
         note: Option<String>,
     },
     /// 移除远程仓库配置
     Remove { name: String },
+    /// 启用远程仓库
+    Enable { name: String },
+    /// 禁用远程仓库，保留配置但不参与 apply/push/check
+    Disable { name: String },
+    /// 将远程仓库设为 yewpb remote 的拉取地址
+    SetPrimary { name: String },
+    /// 调整远程仓库在配置中的顺序
+    Move {
+        name: String,
+        /// 移动到指定远程仓库之前
+        #[arg(
+            long = "before",
+            conflicts_with = "after",
+            required_unless_present = "after"
+        )]
+        before: Option<String>,
+        /// 移动到指定远程仓库之后
+        #[arg(long = "after")]
+        after: Option<String>,
+    },
     /// 列出所有远程仓库
     List {
         /// 显示完整详情
@@ -130,18 +214,166 @@ pub enum ConfigCommands {
     },
     /// 导出配置到文件
     Export {
+        /// 输出文件，- 表示标准输出（未指定且标准输出被重定向时同样输出到标准输出）
         #[arg(short = 'o', long = "output")]
         output: Option<PathBuf>,
+        /// 导出格式，默认根据文件扩展名推断，无法推断时使用 toml
+        #[arg(long = "format", value_enum)]
+        format: Option<ConfigFormat>,
     },
     /// 从文件导入配置
     Import {
-        #[arg(short = 'i', long = "input")]
+        /// 导入文件，- 表示标准输入
+        #[arg(short = 'i', long = "input", conflicts_with = "from_repo")]
         input: Option<PathBuf>,
         #[arg(short = 'm', long = "merge")]
         merge: bool,
+        /// 导入格式，默认根据文件扩展名或内容推断
+        #[arg(long = "format", value_enum)]
+        format: Option<ConfigFormat>,
+        /// 仅显示导入后的变更，不实际修改
+        #[arg(short = 'd', long = "dry-run")]
+        dry_run: bool,
+        /// 合并时同名条目的处理方式
+        #[arg(long = "on-conflict", value_enum, default_value = "theirs")]
+        on_conflict: OnConflict,
+        /// 根据已有 git 仓库（默认当前目录）的远程地址生成配置，并合并到当前配置
+        #[arg(
+            long = "from-repo",
+            value_name = "PATH",
+            num_args = 0..=1,
+            default_missing_value = "."
+        )]
+        from_repo: Option<PathBuf>,
+        /// 保存前不再确认（用于 --from-repo）
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
     },
     /// 使用默认编辑器打开配置文件
     Edit,
+    /// 列出配置文件的历史备份
+    History,
+    /// 恢复最近一次备份
+    Undo,
+    /// 恢复指定编号的备份
+    Restore {
+        /// 备份编号，可以只写唯一的前缀
+        id: String,
+    },
+    /// 校验配置文件，报告语法错误、未知配置项、重复名称与重叠地址等问题
+    Validate {
+        /// 要校验的文件，默认校验所有存在的系统、用户与项目配置文件
+        path: Option<PathBuf>,
+    },
+    /// 显示叠加所有配置层后最终生效的配置
+    Show {
+        /// 同时显示每个值的来源（内置默认值、系统/用户/项目配置文件或环境变量）
+        #[arg(long = "origin")]
+        origin: bool,
+    },
+    /// 管理远程仓库分组
+    Group {
+        #[command(subcommand)]
+        command: GroupCommands,
+    },
+    /// 管理 [defaults] 中的默认配置项
+    Defaults {
+        #[command(subcommand)]
+        command: DefaultsCommands,
+    },
+}
+
+/// 仓库名称映射参数
+#[derive(Args)]
+pub struct RenameArgs {
+    /// 该平台上固定使用的仓库名称
+    #[arg(long = "repo-name")]
+    pub repo_name: Option<String>,
+    /// 为仓库名称添加前缀
+    #[arg(long = "repo-prefix")]
+    pub repo_prefix: Option<String>,
+    /// 为仓库名称添加后缀（如 -mirror）
+    #[arg(long = "repo-suffix")]
+    pub repo_suffix: Option<String>,
+    /// 仓库名称大小写转换
+    #[arg(long = "repo-case", value_enum)]
+    pub repo_case: Option<NameCase>,
+    /// 使用正则表达式替换仓库名称，支持 $1 等捕获组引用
+    #[arg(long = "repo-replace", num_args = 2, value_names = ["PATTERN", "REPLACEMENT"])]
+    pub repo_replace: Vec<String>,
+    /// 清除已有的仓库名称映射规则
+    #[arg(long = "clear-rename")]
+    pub clear_rename: bool,
+}
+
+/// 单个远程仓库的推送策略参数
+#[derive(Args)]
+pub struct PolicyArgs {
+    /// 推送该仓库的超时时间（秒），覆盖 defaults.timeout
+    #[arg(long = "timeout")]
+    pub timeout: Option<u64>,
+    /// 推送该仓库失败时的最大重试次数
+    #[arg(long = "retry")]
+    pub retry: Option<u32>,
+    /// 推送该仓库的重试间隔毫秒数
+    #[arg(long = "retry-delay")]
+    pub retry_delay: Option<u64>,
+    /// 是否允许强制推送，设为 false 表示受保护的仓库
+    #[arg(long = "allow-force")]
+    pub allow_force: Option<bool>,
+    /// 推送该仓库时追加的 git 参数，可多次使用（会覆盖已有的值）
+    #[arg(long = "extra-args", allow_hyphen_values = true)]
+    pub extra_args: Vec<String>,
+    /// 推送该仓库时是否跳过连接验证
+    #[arg(long = "skip-check")]
+    pub skip_check: Option<bool>,
+    /// 清除该仓库的所有推送策略
+    #[arg(long = "clear-policy")]
+    pub clear_policy: bool,
+}
+
+/// 分组管理子命令
+#[derive(Subcommand)]
+pub enum GroupCommands {
+    /// 创建或覆盖分组
+    Set {
+        name: String,
+        /// 分组包含的远程仓库名称
+        #[arg(required = true)]
+        remotes: Vec<String>,
+    },
+    /// 移除分组
+    Remove { name: String },
+    /// 列出所有分组
+    List,
+}
+
+/// 导入合并时同名条目的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OnConflict {
+    /// 保留当前配置
+    Keep,
+    /// 使用导入的配置
+    Theirs,
+    /// 逐项询问
+    Ask,
+}
+
+/// 默认配置项管理子命令
+#[derive(Subcommand)]
+pub enum DefaultsCommands {
+    /// 输出配置项最终生效的值
+    Get { key: String },
+    /// 设置配置项，写入用户配置文件
+    Set {
+        key: String,
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+    },
+    /// 从用户配置文件中移除配置项，恢复为默认值
+    Unset { key: String },
+    /// 列出所有可用的配置项及其说明
+    Keys,
 }
 
 /// Hook 管理子命令
@@ -1,35 +1,41 @@
-use crate::config::{load_config, DEFAULT_ALIAS};
-use crate::git::{
-    check_git_available, get_git_alias, set_git_alias, unset_git_alias, which_command,
-};
 use anyhow::Result;
 use std::path::Path;
+use yewpb::config::{load_layered_config, Defaults};
+use yewpb::git::{which_command, GitBackend};
 
-pub fn execute(config_path: &Path, name: Option<String>, remove: bool, show: bool) -> Result<()> {
-    check_git_available()?;
+pub fn execute(
+    git: &dyn GitBackend,
+    config_path: &Path,
+    name: Option<String>,
+    remove: bool,
+    show: bool,
+) -> Result<()> {
+    git.check_available()?;
 
     // 确定要使用的别名名称
-    let config = load_config(config_path)?;
-    let alias_name = name
-        .or(config.defaults.alias.clone())
-        .unwrap_or_else(|| DEFAULT_ALIAS.to_string());
+    let alias_name = load_layered_config(config_path)?
+        .resolve(&Defaults {
+            alias: name,
+            ..Default::default()
+        })
+        .alias;
 
     // 显示模式
     if show {
-        return show_alias_status(&alias_name);
+        return show_alias_status(git, &alias_name);
     }
 
     // 删除模式
     if remove {
-        return remove_alias(&alias_name);
+        return remove_alias(git, &alias_name);
     }
 
     // 安装模式
-    install_alias(&alias_name)
+    install_alias(git, &alias_name)
 }
 
-fn show_alias_status(name: &str) -> Result<()> {
-    match get_git_alias(name)? {
+fn show_alias_status(git: &dyn GitBackend, name: &str) -> Result<()> {
+    match git.config_get(&alias_key(name))? {
         Some(value) => {
             if value.contains("yewpb") {
                 println!("别名 'git {}' 已配置，指向: {}", name, value);
@@ -44,15 +50,15 @@ fn show_alias_status(name: &str) -> Result<()> {
     Ok(())
 }
 
-fn remove_alias(name: &str) -> Result<()> {
+fn remove_alias(git: &dyn GitBackend, name: &str) -> Result<()> {
     // 检查别名是否存在
-    match get_git_alias(name)? {
+    match git.config_get(&alias_key(name))? {
         Some(value) => {
             // 检查是否是我们创建的别名
             if !value.contains("yewpb") {
                 println!("警告: 别名 'git {}' 不是由本工具创建，仍将删除", name);
             }
-            unset_git_alias(name)?;
+            git.config_unset(&alias_key(name))?;
             println!("已删除别名: git {}", name);
         }
         None => {
@@ -62,7 +68,7 @@ fn remove_alias(name: &str) -> Result<()> {
     Ok(())
 }
 
-fn install_alias(name: &str) -> Result<()> {
+fn install_alias(git: &dyn GitBackend, name: &str) -> Result<()> {
     // 查找 yewpb 的完整路径
     let cmd_path = which_command("yewpb")?;
 
@@ -86,7 +92,7 @@ fn install_alias(name: &str) -> Result<()> {
     };
 
     // 检查是否已存在
-    if let Some(existing) = get_git_alias(name)? {
+    if let Some(existing) = git.config_get(&alias_key(name))? {
         if existing == alias_value {
             println!("别名 'git {}' 已是最新配置", name);
             return Ok(());
@@ -94,9 +100,40 @@ fn install_alias(name: &str) -> Result<()> {
         println!("更新已存在的别名: git {}", name);
     }
 
-    set_git_alias(name, &alias_value)?;
+    git.config_set(&alias_key(name), &alias_value)?;
     println!("已设置别名: git {} -> {}", name, alias_value);
     println!("\n现在可以使用 'git {}' 来运行 yewpb", name);
 
     Ok(())
 }
+
+/// git 别名对应的全局配置项
+fn alias_key(name: &str) -> String {
+    format!("alias.{}", name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yewpb::git::FakeGit;
+
+    #[test]
+    fn remove_deletes_alias() {
+        let git = FakeGit::new().with_config("alias.pb", "!yewpb");
+
+        remove_alias(&git, "pb").unwrap();
+
+        assert_eq!(git.config("alias.pb"), None);
+    }
+
+    #[test]
+    fn install_overwrites_foreign_alias() {
+        let git = FakeGit::new().with_config("alias.pb", "!other-tool");
+
+        install_alias(&git, "pb").unwrap();
+
+        let value = git.config("alias.pb").unwrap();
+        assert!(value.starts_with('!'));
+        assert_ne!(value, "!other-tool");
+    }
+}
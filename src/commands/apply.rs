@@ -1,74 +1,82 @@
-use crate::config::load_config;
-use crate::git::{
-    check_git_available, check_remote_available, ensure_git_repo, git_remote_names,
-    install_pre_push_hook, is_push_backup_hook_installed, run_git_add_push_url, run_git_add_remote,
-    run_git_get_remote_url, run_git_remote_remove,
-};
-use crate::utils::build_remote_url;
+use super::reachability::{check_all, Probe};
 use anyhow::Result;
+use std::collections::HashSet;
 use std::env;
 use std::io::{self, Write};
 use std::path::Path;
+use yewpb::config::{load_layered_config_from, ConfigSources, Defaults};
+use yewpb::git::{install_pre_push_hook, is_push_backup_hook_installed, GitBackend, GitOverrides};
+use yewpb::layout::{DesiredRemotes, RemoteChange, RemotePlan};
+use yewpb::snapshot::save_snapshot;
+use yewpb::utils::repo_name_from_url;
+use yewpb::{Config, Layout, REMOTE_NAME};
 
-const REMOTE_NAME: &str = "yewpb";
+/// apply 命令的参数
+pub struct ApplyArgs {
+    pub repo: Option<String>,
+    pub yes: bool,
+    /// 命令行指定的可用性检查超时时间（秒），None 时使用配置中的 check_timeout
+    pub timeout: Option<u64>,
+    pub dry_run: bool,
+    pub no_hook: bool,
+    /// 命令行指定的分组，None 时使用配置中的 defaults.group
+    pub group: Option<String>,
+    /// 在 git 中创建远程仓库的方式
+    pub layout: Layout,
+    /// 同时删除不属于任何配置的推送地址
+    pub prune: bool,
+    /// 同时检查可用性的最大数量，None 表示使用 [`DEFAULT_JOBS`](super::reachability::DEFAULT_JOBS)
+    pub jobs: Option<usize>,
+}
 
 pub fn execute(
+    git: &dyn GitBackend,
     config_path: &Path,
-    repo: Option<String>,
-    yes: bool,
-    timeout: u64,
-    dry_run: bool,
-    no_hook: bool,
+    sources: &ConfigSources,
+    args: ApplyArgs,
 ) -> Result<()> {
-    check_git_available()?;
-    let config = load_config(config_path)?;
+    let ApplyArgs {
+        repo,
+        yes,
+        timeout,
+        dry_run,
+        no_hook,
+        group,
+        layout,
+        prune,
+        jobs,
+    } = args;
+    git.check_available()?;
+    let layered = load_layered_config_from(config_path, sources)?;
+    let resolved = layered.resolve(&Defaults {
+        check_timeout: timeout,
+        group,
+        ..Default::default()
+    });
+    let mut config = layered.config;
+    // 完整配置用于识别仓库中由 yewpb 生成的地址
+    let known = config.clone();
+    if let Some(group) = &resolved.group {
+        config.retain_group(group)?;
+        println!("使用分组: {}", group);
+    }
+    for disabled in &config.disabled {
+        println!("- 跳过 {}（{}）", disabled.remote.name, disabled.reason);
+    }
     if config.remotes.is_empty() {
         println!("没有保存的远程仓库配置。");
         return Ok(());
     }
-    ensure_git_repo()?;
-    let existing = git_remote_names()?;
+    git.ensure_repo()?;
+    let existing = git.remote_names()?;
+    let cwd = env::current_dir()?;
 
     // 确定仓库名称
     let repo = match repo {
         Some(name) => name,
         None => {
-            let mut detected_name = None;
-
-            // 1. 尝试从现有 remote 推断
-            // 优先查找 origin，否则取任意一个非 yewpb 的 remote
-            let remote_candidate = if existing.contains("origin") {
-                Some("origin")
-            } else {
-                existing
-                    .iter()
-                    .find(|&n| n != REMOTE_NAME)
-                    .map(|s| s.as_str())
-            };
-
-            if let Some(remote) = remote_candidate {
-                if let Ok(url) = run_git_get_remote_url(remote) {
-                    let url = url.trim();
-                    let url = url.strip_suffix(".git").unwrap_or(url);
-                    if let Some(name) = url.rsplit('/').next() {
-                        if !name.is_empty() {
-                            detected_name = Some(name.to_string());
-                        }
-                    }
-                }
-            }
-
-            // 2. 尝试从目录名推断
-            if detected_name.is_none() {
-                if let Ok(cwd) = env::current_dir() {
-                    if let Some(name) = cwd.file_name() {
-                        detected_name = Some(name.to_string_lossy().to_string());
-                    }
-                }
-            }
-
-            let name =
-                detected_name.ok_or_else(|| anyhow::anyhow!("无法自动检测仓库名称，请手动指定"))?;
+            let name = detect_repo_name(git, &existing, &cwd)
+                .ok_or_else(|| anyhow::anyhow!("无法自动检测仓库名称，请手动指定"))?;
 
             println!("检测到仓库名称为: {}", name);
             if !yes {
@@ -89,62 +97,59 @@ pub fn execute(
         }
     };
 
-    // 1. 清理旧的独立远程仓库（如果存在）
+    // 计算所有 URL，模板有误时在修改仓库之前报错
+    let targets = Targets::build(&config, &repo, &cwd, layout)?;
+    println!("各远程仓库使用的仓库名称:");
     for remote in &config.remotes {
-        if existing.contains(&remote.name) && remote.name != REMOTE_NAME {
-            if dry_run {
-                println!("[dry-run] 将执行: git remote remove {}", remote.name);
-            } else {
-                run_git_remote_remove(&remote.name)?;
-                println!("已清理旧远程仓库: {}", remote.name);
-            }
-        }
+        println!("  {:12} {}", remote.name, remote.repo_name(&repo)?);
     }
-
-    // 2. 重置 yewpb 远程仓库
-    if existing.contains(REMOTE_NAME) {
-        if dry_run {
-            println!("[dry-run] 将执行: git remote remove {}", REMOTE_NAME);
-        } else {
-            run_git_remote_remove(REMOTE_NAME)?;
-        }
+    if let Some(warning) = &targets.warning {
+        println!("警告: {}", warning);
     }
+    let Targets {
+        desired,
+        urls: remote_urls,
+        ..
+    } = targets;
 
-    // 计算所有 URL
-    let mut remote_urls = Vec::new();
-    for remote in &config.remotes {
-        let url = build_remote_url(&remote.base, &repo);
-        remote_urls.push((remote.name.clone(), url));
-    }
-
-    // 3. 创建 yewpb 远程仓库
-    // 使用第一个 URL 作为 fetch URL
-    if let Some((_, first_url)) = remote_urls.first() {
-        if dry_run {
-            println!(
-                "[dry-run] 将执行: git remote add {} {}",
-                REMOTE_NAME, first_url
-            );
-        } else {
-            run_git_add_remote(REMOTE_NAME, first_url)?;
-            println!("已配置统一远程仓库: {}", REMOTE_NAME);
+    // 比较当前与期望的远程仓库，只执行差异部分
+    let plan = RemotePlan::compute(git, &known, &desired, prune)?;
+    print_plan(&plan);
+
+    if plan.is_noop() {
+        println!("远程仓库已是最新，无需修改。");
+    } else if dry_run {
+        for command in plan.changes.iter().filter_map(RemoteChange::command) {
+            println!("[dry-run] 将执行: {}", command);
         }
+    } else {
+        let snapshot = save_snapshot(git, "apply")?;
+        plan.apply(git)?;
+        let count = plan.changes.iter().filter(|c| c.is_change()).count();
+        println!("已应用 {} 项变更。", count);
+        println!(
+            "修改前的远程仓库已保存为快照 {}，可使用 yewpb restore 恢复。",
+            snapshot.id
+        );
     }
 
-    // 4. 添加所有 push URL 并检查可用性
-    for (name, url) in remote_urls {
-        if dry_run {
-            println!(
-                "[dry-run] 将执行: git remote set-url --add --push {} {}",
-                REMOTE_NAME, url
-            );
-        } else {
-            // 添加 push URL
-            run_git_add_push_url(REMOTE_NAME, &url)?;
-
-            // 检查可用性 (使用 URL 进行检查)
-            print!("检查远程仓库 '{}' ({}) 的可用性...", name, url);
-            match check_remote_available(&url, timeout) {
+    // 只检查新增或修改的地址的可用性，并发检查后按配置顺序输出
+    if !dry_run {
+        let new_urls = plan.new_urls();
+        let probes: Vec<Probe> = remote_urls
+            .into_iter()
+            .filter(|(_, url, _)| new_urls.contains(&url.as_str()))
+            .map(|(name, url, overrides)| Probe {
+                name,
+                url,
+                overrides,
+                timeout: resolved.check_timeout,
+            })
+            .collect();
+        let results = check_all(git, &probes, jobs)?;
+        for (probe, result) in probes.iter().zip(results) {
+            print!("检查远程仓库 '{}' ({}) 的可用性...", probe.name, probe.url);
+            match result {
                 Ok(true) => println!(" ✓ 可访问"),
                 Ok(false) => println!(" ✗ 无法访问（可能需要配置认证或网络不通）"),
                 Err(e) => println!(" ✗ 检查失败: {}", e),
@@ -175,11 +180,11 @@ pub fn execute(
 
         if should_install_hook {
             println!();
-            let already_installed = is_push_backup_hook_installed().unwrap_or(false);
+            let already_installed = is_push_backup_hook_installed(git).unwrap_or(false);
             if already_installed {
                 println!("pre-push hook 已存在，跳过安装");
             } else {
-                match install_pre_push_hook() {
+                match install_pre_push_hook(git) {
                     Ok(_) => {
                         println!("pre-push hook 已成功安装");
                         println!("现在推送到 origin 时会自动同步到所有配置的远程仓库");
@@ -195,3 +200,348 @@ pub fn execute(
 
     Ok(())
 }
+
+/// 推断仓库名称：优先使用 origin 的地址，否则取任意一个非 yewpb 的 remote，都没有时使用目录名
+pub(super) fn detect_repo_name(
+    git: &dyn GitBackend,
+    existing: &HashSet<String>,
+    dir: &Path,
+) -> Option<String> {
+    let remote_candidate = if existing.contains("origin") {
+        Some("origin")
+    } else {
+        // 按名称排序，保证结果稳定
+        let mut names: Vec<&String> = existing.iter().filter(|&n| n != REMOTE_NAME).collect();
+        names.sort();
+        names.first().map(|s| s.as_str())
+    };
+    if let Some(url) = remote_candidate.and_then(|remote| git.remote_url(remote).ok()) {
+        if let Some(name) = repo_name_from_url(&url) {
+            return Some(name);
+        }
+    }
+    dir.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// 某个仓库期望的远程仓库状态，以及检查可用性所需的参数
+pub(super) struct Targets {
+    pub desired: DesiredRemotes,
+    /// (名称, 地址, git 调用参数)，按配置顺序排列
+    pub urls: Vec<(String, String, GitOverrides)>,
+    /// 选择拉取地址时的警告
+    pub warning: Option<String>,
+}
+
+impl Targets {
+    /// 按配置为仓库生成所有地址，模板有误时报错
+    pub fn build(config: &Config, repo: &str, dir: &Path, layout: Layout) -> Result<Self> {
+        let dir_name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut urls = Vec::new();
+        for remote in &config.remotes {
+            urls.push((
+                remote.name.clone(),
+                remote.build_url(repo, &dir_name)?,
+                remote.git_overrides(&config.defaults),
+            ));
+        }
+
+        // 确定拉取地址
+        let (fetch_remote, warning) = config.fetch_remote();
+        let fetch_url = fetch_remote.and_then(|fetch| {
+            urls.iter()
+                .find(|(name, _, _)| name == &fetch.name)
+                .map(|(_, url, _)| url.clone())
+        });
+        let desired = DesiredRemotes {
+            layout,
+            fetch_url,
+            remotes: urls
+                .iter()
+                .map(|(name, url, _)| (name.clone(), url.clone()))
+                .collect(),
+        };
+        Ok(Targets {
+            desired,
+            urls,
+            warning,
+        })
+    }
+}
+
+/// 按远程仓库列出变更计划：+ 新增，- 删除，~ 修改，= 保持不变
+pub(super) fn print_plan(plan: &RemotePlan) {
+    println!("远程仓库变更计划:");
+    for change in &plan.changes {
+        match change {
+            RemoteChange::AddRemote { remote, url } => {
+                println!("  + {:12} 远程仓库 {}", remote, url)
+            }
+            RemoteChange::SetUrl { remote, from, to } => {
+                println!("  ~ {:12} 拉取地址 {} -> {}", remote, from, to)
+            }
+            RemoteChange::RemoveRemote { remote } => println!("  - {:12} 远程仓库", remote),
+            RemoteChange::AddPushUrl { remote, url } => {
+                println!("  + {:12} 推送地址 {}", remote, url)
+            }
+            RemoteChange::RemovePushUrl { remote, url } => {
+                println!("  - {:12} 推送地址 {}", remote, url)
+            }
+            RemoteChange::Keep {
+                remote,
+                url,
+                manual,
+            } => println!(
+                "  = {:12} {}{}",
+                remote,
+                url,
+                if *manual {
+                    "（手动添加，保留）"
+                } else {
+                    ""
+                }
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yewpb::git::fixtures::{self, A, B};
+    use yewpb::git::{FakeBehavior, FakeGit};
+    use yewpb::layout::MANAGED_PUSH_URLS;
+
+    /// b 是主仓库
+    fn write_config(dir: &Path) -> std::path::PathBuf {
+        let mut config = fixtures::config();
+        config.remotes[1].primary = true;
+        fixtures::write_config(dir, &config)
+    }
+
+    fn args(dry_run: bool) -> ApplyArgs {
+        ApplyArgs {
+            repo: Some("demo".to_string()),
+            yes: true,
+            timeout: Some(5),
+            dry_run,
+            no_hook: true,
+            group: None,
+            layout: Layout::Unified,
+            prune: false,
+            jobs: None,
+        }
+    }
+
+    #[test]
+    fn replaces_old_remotes_with_unified_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote("origin", "https://origin.example.com/demo.git")
+            .with_remote("a", A)
+            .with_remote(REMOTE_NAME, "https://stale.example.com/demo.git")
+            .with_server(A, FakeBehavior::Ok)
+            .with_server(B, FakeBehavior::AuthFailure);
+
+        execute(
+            &git,
+            &write_config(dir.path()),
+            &ConfigSources::isolated(dir.path()),
+            args(false),
+        )
+        .unwrap();
+
+        assert!(git.remote("a").is_none());
+        assert!(git.remote("origin").is_some());
+        let (fetch, push) = git.remote(REMOTE_NAME).unwrap();
+        assert_eq!(fetch, B);
+        assert_eq!(push, vec![A, B]);
+        // 不可访问的仓库只提示，不影响配置
+        assert!(git.calls().contains(&format!("ls-remote {}", B)));
+    }
+
+    #[test]
+    fn dry_run_leaves_remotes_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new().with_git_dir(dir.path()).with_remote("a", A);
+
+        execute(
+            &git,
+            &write_config(dir.path()),
+            &ConfigSources::isolated(dir.path()),
+            args(true),
+        )
+        .unwrap();
+
+        assert_eq!(git.remote("a").unwrap().0, A);
+        assert!(git.remote(REMOTE_NAME).is_none());
+        assert!(git.calls().is_empty());
+    }
+
+    #[test]
+    fn split_layout_replaces_unified_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote(REMOTE_NAME, A)
+            .with_remote("a", "https://stale.example.com/demo.git");
+
+        let args = ApplyArgs {
+            layout: Layout::Split,
+            ..args(false)
+        };
+        execute(
+            &git,
+            &write_config(dir.path()),
+            &ConfigSources::isolated(dir.path()),
+            args,
+        )
+        .unwrap();
+
+        assert!(git.remote(REMOTE_NAME).is_none());
+        assert_eq!(git.remote("a"), Some((A.to_string(), vec![])));
+        assert_eq!(git.remote("b"), Some((B.to_string(), vec![])));
+    }
+
+    #[test]
+    fn both_layout_keeps_unified_and_split_remotes() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new().with_git_dir(dir.path());
+
+        let args = ApplyArgs {
+            layout: Layout::Both,
+            ..args(false)
+        };
+        execute(
+            &git,
+            &write_config(dir.path()),
+            &ConfigSources::isolated(dir.path()),
+            args,
+        )
+        .unwrap();
+
+        assert_eq!(git.remote(REMOTE_NAME).unwrap().1, vec![A, B]);
+        assert_eq!(git.remote("a").unwrap().0, A);
+        assert_eq!(git.remote("b").unwrap().0, B);
+    }
+
+    #[test]
+    fn second_run_changes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = write_config(dir.path());
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_server(A, FakeBehavior::Ok)
+            .with_server(B, FakeBehavior::Ok);
+        execute(
+            &git,
+            &config,
+            &ConfigSources::isolated(dir.path()),
+            args(false),
+        )
+        .unwrap();
+        let calls = git.calls().len();
+
+        execute(
+            &git,
+            &config,
+            &ConfigSources::isolated(dir.path()),
+            args(false),
+        )
+        .unwrap();
+
+        // 没有任何修改，也不再重复检查可用性
+        assert_eq!(git.calls().len(), calls);
+    }
+
+    #[test]
+    fn keeps_manual_push_urls_unless_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = write_config(dir.path());
+        let manual = "https://backup.example.com/demo.git";
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote(REMOTE_NAME, B)
+            .with_push_url(REMOTE_NAME, A)
+            .with_push_url(REMOTE_NAME, manual)
+            .with_push_url(REMOTE_NAME, "https://a.example.com/old-name.git");
+
+        execute(
+            &git,
+            &config,
+            &ConfigSources::isolated(dir.path()),
+            args(false),
+        )
+        .unwrap();
+        assert_eq!(git.remote(REMOTE_NAME).unwrap().1, vec![A, manual, B]);
+        assert_eq!(
+            git.calls(),
+            vec![
+                format!("config --local --add {} {}", MANAGED_PUSH_URLS, A),
+                format!(
+                    r"config --local --unset-all remote.{}.pushurl '^https://a\.example\.com/old-name\.git$'",
+                    REMOTE_NAME
+                ),
+                format!(
+                    r"config --local --unset-all {} '^https://a\.example\.com/old-name\.git$'",
+                    MANAGED_PUSH_URLS
+                ),
+                format!("remote set-url --add --push {} {}", REMOTE_NAME, B),
+                format!("config --local --add {} {}", MANAGED_PUSH_URLS, B),
+                format!("ls-remote {}", B),
+            ]
+        );
+        assert_eq!(git.repo_config(MANAGED_PUSH_URLS), vec![A, B]);
+
+        let args = ApplyArgs {
+            prune: true,
+            ..args(false)
+        };
+        execute(&git, &config, &ConfigSources::isolated(dir.path()), args).unwrap();
+        assert_eq!(git.remote(REMOTE_NAME).unwrap().1, vec![A, B]);
+    }
+
+    #[test]
+    fn removes_recorded_push_url_after_base_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = write_config(dir.path());
+        let old = "https://old-b.example.com/demo.git";
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote(REMOTE_NAME, B)
+            .with_push_url(REMOTE_NAME, A)
+            .with_push_url(REMOTE_NAME, old)
+            .with_repo_config(MANAGED_PUSH_URLS, A)
+            .with_repo_config(MANAGED_PUSH_URLS, old);
+
+        execute(
+            &git,
+            &config,
+            &ConfigSources::isolated(dir.path()),
+            args(false),
+        )
+        .unwrap();
+
+        assert_eq!(git.remote(REMOTE_NAME).unwrap().1, vec![A, B]);
+        assert_eq!(git.repo_config(MANAGED_PUSH_URLS), vec![A, B]);
+    }
+
+    #[test]
+    fn requires_git_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new().outside_repo();
+
+        let err = execute(
+            &git,
+            &write_config(dir.path()),
+            &ConfigSources::isolated(dir.path()),
+            args(false),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("不是 git 仓库"));
+    }
+}
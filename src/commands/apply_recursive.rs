@@ -0,0 +1,484 @@
+use super::apply::{detect_repo_name, print_plan, ApplyArgs, Targets};
+use super::reachability::{check_all, Probe};
+use anyhow::{bail, Result};
+use console::{measure_text_width, pad_str, Alignment};
+use regex::Regex;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use yewpb::config::{load_layered_config_in, Defaults};
+use yewpb::git::{
+    install_pre_push_hook, is_push_backup_hook_installed, GitBackend, GitOverrides, ProcessGit,
+};
+use yewpb::layout::{RemoteChange, RemotePlan};
+use yewpb::snapshot::save_snapshot;
+
+/// apply --recursive 查找仓库的范围
+pub struct ScanArgs {
+    pub root: PathBuf,
+    /// 只处理相对路径匹配任一 glob 的仓库，为空时不限制
+    pub include: Vec<String>,
+    /// 跳过匹配任一 glob 的目录与仓库
+    pub exclude: Vec<String>,
+    /// 向下查找的最大层数，0 表示只检查 root 本身
+    pub max_depth: usize,
+}
+
+/// 单个仓库的处理结果
+struct RepoRow {
+    /// 相对于 root 的路径
+    path: String,
+    name: Option<String>,
+    plan: Option<RemotePlan>,
+    /// 需要检查可用性的 (远程仓库名称, 地址, git 调用参数)
+    checks: Vec<(String, String, GitOverrides)>,
+    /// 该仓库配置中的 check_timeout，命令行参数优先
+    check_timeout: u64,
+    error: Option<String>,
+    /// 远程仓库已修改，但 pre-push hook 安装失败
+    hook_error: Option<String>,
+}
+
+pub fn execute(config_path: &Path, args: ApplyArgs, scan: ScanArgs) -> Result<()> {
+    ProcessGit::new().check_available()?;
+    let repos = find_repos(&scan)?;
+    if repos.is_empty() {
+        println!("在 {} 下没有找到 git 仓库。", scan.root.display());
+        return Ok(());
+    }
+    println!(
+        "在 {} 下找到 {} 个 git 仓库。",
+        scan.root.display(),
+        repos.len()
+    );
+    if let Some(group) = &args.group {
+        println!("使用分组: {}", group);
+    }
+    let install_hook = !args.dry_run && !args.no_hook && confirm_hook(args.yes, repos.len())?;
+
+    let rows: Vec<RepoRow> = repos
+        .iter()
+        .map(|dir| {
+            let mut row = RepoRow {
+                path: relative_path(&scan.root, dir),
+                name: None,
+                plan: None,
+                checks: Vec::new(),
+                check_timeout: 0,
+                error: None,
+                hook_error: None,
+            };
+            let git = ProcessGit::in_dir(dir);
+            if let Err(e) = apply_repo(&git, config_path, dir, &args, install_hook, &mut row) {
+                row.error = Some(e.to_string());
+            }
+            row
+        })
+        .collect();
+
+    // 所有仓库新增的地址一起并发检查
+    let checks: Vec<(usize, &(String, String, GitOverrides))> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| row.checks.iter().map(move |check| (i, check)))
+        .collect();
+    let probes: Vec<Probe> = checks
+        .iter()
+        .map(|&(i, (name, url, overrides))| Probe {
+            name: format!("{}: {}", rows[i].path, name),
+            url: url.clone(),
+            overrides: overrides.clone(),
+            timeout: rows[i].check_timeout,
+        })
+        .collect();
+    let results = check_all(&ProcessGit::new(), &probes, args.jobs)?;
+    let mut unreachable = vec![Vec::new(); rows.len()];
+    for (&(i, (name, url, _)), result) in checks.iter().zip(results) {
+        let problem = match result {
+            Ok(true) => continue,
+            Ok(false) => "无法访问".to_string(),
+            Err(e) => format!("检查失败: {}", e),
+        };
+        unreachable[i].push(format!("{} ({}) {}", name, url, problem));
+    }
+
+    print_summary(&rows, &unreachable, args.dry_run);
+
+    let failed = rows.iter().filter(|row| row.error.is_some()).count();
+    if failed > 0 {
+        bail!("{} 个仓库应用失败", failed);
+    }
+    let hook_failed = rows.iter().filter(|row| row.hook_error.is_some()).count();
+    if hook_failed > 0 {
+        bail!("{} 个仓库的 pre-push hook 安装失败", hook_failed);
+    }
+    Ok(())
+}
+
+/// 批量模式会修改每个仓库的 .git/hooks/pre-push，未指定 -y 时先统一询问一次（默认不安装）
+fn confirm_hook(yes: bool, repos: usize) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    print!(
+        "是否为这 {} 个仓库安装 pre-push hook 以自动同步到所有远程? (y/N) ",
+        repos
+    );
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim();
+    let install = input.eq_ignore_ascii_case("y") || input.eq_ignore_ascii_case("yes");
+    if !install {
+        println!("不安装 pre-push hook，之后可以在仓库中运行 yewpb hook install");
+    }
+    Ok(install)
+}
+
+/// 在单个仓库中计算并执行变更计划，结果写入 row
+fn apply_repo(
+    git: &dyn GitBackend,
+    config_path: &Path,
+    dir: &Path,
+    args: &ApplyArgs,
+    install_hook: bool,
+    row: &mut RepoRow,
+) -> Result<()> {
+    git.ensure_repo()?;
+    // 未在命令行指定时，分组与检查超时都使用仓库自己的配置
+    let layered = load_layered_config_in(config_path, Some(dir))?;
+    let resolved = layered.resolve(&Defaults {
+        check_timeout: args.timeout,
+        group: args.group.clone(),
+        ..Default::default()
+    });
+    row.check_timeout = resolved.check_timeout;
+    let mut config = layered.config;
+    let known = config.clone();
+    if let Some(group) = &resolved.group {
+        config.retain_group(group)?;
+    }
+    if config.remotes.is_empty() {
+        bail!("没有保存的远程仓库配置");
+    }
+
+    let name = detect_repo_name(git, &git.remote_names()?, dir)
+        .ok_or_else(|| anyhow::anyhow!("无法自动检测仓库名称"))?;
+    row.name = Some(name.clone());
+    let targets = Targets::build(&config, &name, dir, args.layout)?;
+    // 先记录计划，之后的步骤失败时汇总表仍能显示该仓库的变更
+    let plan = row.plan.insert(RemotePlan::compute(
+        git,
+        &known,
+        &targets.desired,
+        args.prune,
+    )?);
+
+    if !plan.is_noop() {
+        println!();
+        println!("{} ({})", row.path, name);
+        print_plan(plan);
+        if !args.dry_run {
+            save_snapshot(git, "apply")?;
+            plan.apply(git)?;
+        }
+    }
+    if !args.dry_run {
+        row.checks = targets
+            .urls
+            .iter()
+            .filter(|(_, url, _)| plan.new_urls().contains(&url.as_str()))
+            .cloned()
+            .collect();
+    }
+    // hook 安装失败不影响已修改的远程仓库，单独报告
+    if install_hook && !is_push_backup_hook_installed(git).unwrap_or(false) {
+        if let Err(e) = install_pre_push_hook(git) {
+            row.hook_error = Some(e.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 输出每个仓库一行的汇总表
+fn print_summary(rows: &[RepoRow], unreachable: &[Vec<String>], dry_run: bool) {
+    let header = ["仓库", "名称", "变更", "可用性", "结果"];
+    let mut table: Vec<[String; 5]> = Vec::new();
+    for (row, unreachable) in rows.iter().zip(unreachable) {
+        let changes = row
+            .plan
+            .as_ref()
+            .map_or_else(|| "-".to_string(), change_counts);
+        let checked = row.checks.len();
+        let availability = if checked == 0 {
+            "-".to_string()
+        } else {
+            format!("{}/{}", checked - unreachable.len(), checked)
+        };
+        let result = match (&row.error, &row.plan) {
+            (Some(e), _) => format!("✗ {}", e),
+            (None, _) if row.hook_error.is_some() => "✗ pre-push hook 安装失败".to_string(),
+            (None, Some(plan)) if plan.is_noop() => "无需修改".to_string(),
+            (None, _) if dry_run => "将修改".to_string(),
+            (None, _) if !unreachable.is_empty() => "已应用，部分地址无法访问".to_string(),
+            (None, _) => "✓ 已应用".to_string(),
+        };
+        table.push([
+            row.path.clone(),
+            row.name.clone().unwrap_or_else(|| "-".to_string()),
+            changes,
+            availability,
+            result,
+        ]);
+    }
+
+    let mut widths = header.map(measure_text_width);
+    for line in &table {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(measure_text_width(cell));
+        }
+    }
+    let format_line = |cells: [&str; 5]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| pad_str(cell, width, Alignment::Left, None).into_owned())
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    println!();
+    println!("{}", format_line(header));
+    for line in &table {
+        println!("{}", format_line(line.each_ref().map(String::as_str)));
+    }
+
+    for (row, unreachable) in rows.iter().zip(unreachable) {
+        for message in unreachable {
+            println!("  ✗ {}: {}", row.path, message);
+        }
+        if let Some(e) = &row.hook_error {
+            println!("  ✗ {}: pre-push hook 安装失败: {}", row.path, e);
+        }
+    }
+
+    let failed = rows.iter().filter(|row| row.error.is_some()).count();
+    let unchanged = rows
+        .iter()
+        .filter(|row| row.error.is_none() && row.plan.as_ref().is_some_and(RemotePlan::is_noop))
+        .count();
+    println!();
+    println!(
+        "共 {} 个仓库: {} {} 个，无需修改 {} 个，失败 {} 个",
+        rows.len(),
+        if dry_run { "将修改" } else { "已修改" },
+        rows.len() - failed - unchanged,
+        unchanged,
+        failed
+    );
+}
+
+/// 变更数量，形如 "+2 -1 ~1"，没有变更时为 "-"
+fn change_counts(plan: &RemotePlan) -> String {
+    let (mut add, mut remove, mut modify) = (0, 0, 0);
+    for change in &plan.changes {
+        match change {
+            RemoteChange::AddRemote { .. } | RemoteChange::AddPushUrl { .. } => add += 1,
+            RemoteChange::RemoveRemote { .. } | RemoteChange::RemovePushUrl { .. } => remove += 1,
+            RemoteChange::SetUrl { .. } => modify += 1,
+            RemoteChange::Keep { .. } => {}
+        }
+    }
+    if add + remove + modify == 0 {
+        return "-".to_string();
+    }
+    format!("+{} -{} ~{}", add, remove, modify)
+}
+
+/// 查找 root 下的 git 仓库，按路径排序；不进入仓库内部与隐藏目录，也不跟随符号链接
+fn find_repos(scan: &ScanArgs) -> Result<Vec<PathBuf>> {
+    if !scan.root.is_dir() {
+        bail!("目录不存在: {}", scan.root.display());
+    }
+    let include = compile_globs(&scan.include)?;
+    let exclude = compile_globs(&scan.exclude)?;
+
+    let mut repos = Vec::new();
+    let mut pending = vec![(scan.root.clone(), 0)];
+    while let Some((dir, depth)) = pending.pop() {
+        let relative = relative_path(&scan.root, &dir);
+        if depth > 0 && exclude.iter().any(|glob| glob.matches(&relative)) {
+            continue;
+        }
+        if dir.join(".git").exists() {
+            if include.is_empty() || include.iter().any(|glob| glob.matches(&relative)) {
+                repos.push(dir);
+            }
+            continue;
+        }
+        if depth == scan.max_depth {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if !hidden && entry.file_type().is_ok_and(|t| t.is_dir()) {
+                pending.push((entry.path(), depth + 1));
+            }
+        }
+    }
+    repos.sort();
+    Ok(repos)
+}
+
+/// 相对于 root 的路径，统一使用 '/' 分隔，root 本身为 "."
+fn relative_path(root: &Path, dir: &Path) -> String {
+    let relative = dir.strip_prefix(root).unwrap_or(dir);
+    if relative.as_os_str().is_empty() {
+        return ".".to_string();
+    }
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 仓库路径的 glob 模式
+///
+/// `*` 与 `?` 不匹配 '/'，`**` 匹配任意层目录；不含 '/' 的模式只匹配最后一级目录名
+struct Glob {
+    regex: Regex,
+    basename: bool,
+}
+
+impl Glob {
+    fn new(pattern: &str) -> Result<Self> {
+        let mut regex = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+        Ok(Glob {
+            regex: Regex::new(&regex)
+                .map_err(|e| anyhow::anyhow!("无效的 glob '{}': {}", pattern, e))?,
+            basename: !pattern.contains('/'),
+        })
+    }
+
+    fn matches(&self, relative: &str) -> bool {
+        let target = if self.basename {
+            relative.rsplit('/').next().unwrap_or(relative)
+        } else {
+            relative
+        };
+        self.regex.is_match(target)
+    }
+}
+
+fn compile_globs(patterns: &[String]) -> Result<Vec<Glob>> {
+    patterns.iter().map(|pattern| Glob::new(pattern)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(root: &Path, include: &[&str], exclude: &[&str], max_depth: usize) -> Vec<String> {
+        let scan = ScanArgs {
+            root: root.to_path_buf(),
+            include: include.iter().map(|s| s.to_string()).collect(),
+            exclude: exclude.iter().map(|s| s.to_string()).collect(),
+            max_depth,
+        };
+        find_repos(&scan)
+            .unwrap()
+            .iter()
+            .map(|dir| relative_path(root, dir))
+            .collect()
+    }
+
+    #[test]
+    fn finds_repos_without_descending_into_them() {
+        let dir = tempfile::tempdir().unwrap();
+        for repo in [
+            "app/.git",
+            "app/vendor/lib/.git",
+            "work/api/.git",
+            "work/deep/er/web/.git",
+            ".hidden/repo/.git",
+        ] {
+            fs::create_dir_all(dir.path().join(repo)).unwrap();
+        }
+        fs::create_dir_all(dir.path().join("notes")).unwrap();
+
+        assert_eq!(scan(dir.path(), &[], &[], 2), vec!["app", "work/api"]);
+        assert_eq!(
+            scan(dir.path(), &[], &[], 4),
+            vec!["app", "work/api", "work/deep/er/web"]
+        );
+        assert_eq!(scan(dir.path(), &[], &[], 0), Vec::<String>::new());
+        assert_eq!(scan(&dir.path().join("app"), &[], &[], 0), vec!["."]);
+    }
+
+    #[test]
+    fn filters_by_include_and_exclude_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        for repo in ["work/api", "work/web", "archive/old-api", "tools/old-cli"] {
+            fs::create_dir_all(dir.path().join(repo).join(".git")).unwrap();
+        }
+
+        assert_eq!(
+            scan(dir.path(), &["work/*"], &[], 3),
+            vec!["work/api", "work/web"]
+        );
+        assert_eq!(
+            scan(dir.path(), &["*api"], &[], 3),
+            vec!["archive/old-api", "work/api"]
+        );
+        assert_eq!(
+            scan(dir.path(), &[], &["archive", "old-*"], 3),
+            vec!["work/api", "work/web"]
+        );
+        assert_eq!(scan(dir.path(), &["**/web"], &[], 3), vec!["work/web"]);
+    }
+
+    #[test]
+    fn counts_changes_by_kind() {
+        let plan = RemotePlan {
+            changes: vec![
+                RemoteChange::AddPushUrl {
+                    remote: "yewpb".to_string(),
+                    url: "a".to_string(),
+                },
+                RemoteChange::RemovePushUrl {
+                    remote: "yewpb".to_string(),
+                    url: "b".to_string(),
+                },
+                RemoteChange::Keep {
+                    remote: "yewpb".to_string(),
+                    url: "c".to_string(),
+                    manual: false,
+                },
+            ],
+        };
+        assert_eq!(change_counts(&plan), "+1 -1 ~0");
+        assert_eq!(change_counts(&RemotePlan::default()), "-");
+    }
+}
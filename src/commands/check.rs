@@ -1,43 +1,69 @@
-use crate::config::{load_config, Config};
-use crate::git::{
-    check_git_available, check_remote_available, ensure_git_repo, git_remote_names,
-    run_git_get_push_urls,
-};
+use super::reachability::{check_all, Probe};
 use anyhow::Result;
 use std::path::Path;
+use yewpb::config::load_merged_config;
+use yewpb::git::GitBackend;
+use yewpb::{ManagedRemotes, REMOTE_NAME};
 
-const REMOTE_NAME: &str = "yewpb";
+pub fn execute(
+    git: &dyn GitBackend,
+    config_path: &Path,
+    timeout: u64,
+    jobs: Option<usize>,
+) -> Result<()> {
+    git.check_available()?;
+    git.ensure_repo()?;
 
-pub fn execute(config_path: &Path, timeout: u64) -> Result<()> {
-    check_git_available()?;
-    ensure_git_repo()?;
+    let config = load_merged_config(config_path)?;
 
-    // 检查 yewpb 远程是否存在
-    let remotes = git_remote_names()?;
-    if !remotes.contains(REMOTE_NAME) {
+    // 检查 yewpb 创建的远程仓库是否存在
+    let managed = ManagedRemotes::detect(git, &config)?;
+    if managed.layout().is_none() {
         println!("错误: 未找到 yewpb 远程仓库。");
         println!("提示: 请先运行 `yewpb apply` 应用配置。");
         return Ok(());
     }
 
-    let urls = run_git_get_push_urls(REMOTE_NAME)?;
+    let urls = managed.push_urls();
 
     if urls.is_empty() {
         println!("错误: 远程仓库 '{}' 未配置推送地址。", REMOTE_NAME);
         return Ok(());
     }
 
-    let config = load_config(config_path)?;
+    // 被禁用的地址不检查，其余地址并发检查后按原顺序输出
+    let mut probes = Vec::new();
+    for url in &urls {
+        if config.match_disabled(url).is_some() {
+            continue;
+        }
+        probes.push(Probe {
+            name: config.display_name(url),
+            url: url.clone(),
+            overrides: config
+                .match_remote(url)
+                .map(|remote| remote.git_overrides(&config.defaults))
+                .unwrap_or_default(),
+            timeout,
+        });
+    }
+    let mut results = check_all(git, &probes, jobs)?.into_iter();
+    let mut probes = probes.iter();
 
     let mut success_count = 0;
     let mut fail_count = 0;
 
-    for url in urls {
-        let name = match_config_name(&config, &url);
-
-        print!("{:12} ", format!("{}:", name));
+    for url in &urls {
+        if let Some((remote, reason)) = config.match_disabled(url) {
+            println!("{:12} - {}", format!("{}:", remote.name), reason);
+            continue;
+        }
+        let (Some(probe), Some(result)) = (probes.next(), results.next()) else {
+            break;
+        };
+        print!("{:12} ", format!("{}:", probe.name));
 
-        match check_remote_available(&url, timeout) {
+        match result {
             Ok(true) => {
                 println!("✓ 连接正常");
                 success_count += 1;
@@ -57,26 +83,3 @@ pub fn execute(config_path: &Path, timeout: u64) -> Result<()> {
 
     Ok(())
 }
-
-/// 匹配 URL 对应的配置名称
-fn match_config_name(config: &Config, url: &str) -> String {
-    // 按 base 长度降序排序，确保最长前缀匹配
-    let mut remotes = config.remotes.clone();
-    remotes.sort_by(|a, b| b.base.len().cmp(&a.base.len()));
-
-    for remote in &remotes {
-        if url.starts_with(&remote.base) {
-            let remainder = &url[remote.base.len()..];
-            if remainder.is_empty()
-                || remote.base.ends_with('/')
-                || remote.base.ends_with(':')
-                || remainder.starts_with('/')
-                || remainder.starts_with(':')
-            {
-                return remote.name.clone();
-            }
-        }
-    }
-
-    "未命名".to_string()
-}
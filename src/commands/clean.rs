@@ -1,24 +1,240 @@
-use crate::git::{check_git_available, ensure_git_repo, git_remote_names, run_git_remote_remove};
-use anyhow::Result;
+use super::restore::restore_snapshot;
+use anyhow::{bail, Result};
+use std::path::Path;
+use yewpb::config::{load_merged_config_from, ConfigSources};
+use yewpb::git::{is_push_backup_hook_installed, uninstall_pre_push_hook, GitBackend};
+use yewpb::layout::MANAGED_PUSH_URLS;
+use yewpb::snapshot::{list_snapshots, save_snapshot};
+use yewpb::{ManagedRemotes, REMOTE_NAME};
 
-const REMOTE_NAME: &str = "yewpb";
+pub fn execute(
+    git: &dyn GitBackend,
+    config_path: &Path,
+    sources: &ConfigSources,
+    dry_run: bool,
+    restore: bool,
+) -> Result<()> {
+    git.check_available()?;
+    git.ensure_repo()?;
 
-pub fn execute(dry_run: bool) -> Result<()> {
-    check_git_available()?;
-    ensure_git_repo()?;
+    if restore {
+        return restore_original(git, dry_run);
+    }
 
-    let existing = git_remote_names()?;
+    // 同时清理统一布局与拆分布局创建的远程仓库
+    let managed = ManagedRemotes::detect(git, &load_merged_config_from(config_path, sources)?)?;
+    let names: Vec<&str> = managed
+        .unified
+        .as_ref()
+        .map(|_| REMOTE_NAME)
+        .into_iter()
+        .chain(managed.split.iter().map(|(name, _)| name.as_str()))
+        .collect();
 
-    if existing.contains(REMOTE_NAME) {
+    if names.is_empty() {
+        println!("未找到远程仓库: {}", REMOTE_NAME);
+    } else if !dry_run {
+        let snapshot = save_snapshot(git, "clean")?;
+        println!(
+            "清理前的远程仓库已保存为快照 {}，可使用 yewpb restore 恢复。",
+            snapshot.id
+        );
+    }
+    for name in names {
         if dry_run {
-            println!("[dry-run] 将执行: git remote remove {}", REMOTE_NAME);
+            println!("[dry-run] 将执行: git remote remove {}", name);
         } else {
-            run_git_remote_remove(REMOTE_NAME)?;
-            println!("已移除远程仓库: {}", REMOTE_NAME);
+            git.remove_remote(name)?;
+            if name == REMOTE_NAME {
+                git.repo_config_unset(MANAGED_PUSH_URLS, None)?;
+            }
+            println!("已移除远程仓库: {}", name);
         }
-    } else {
-        println!("未找到远程仓库: {}", REMOTE_NAME);
     }
 
     Ok(())
 }
+
+/// 恢复到使用 yewpb 之前的状态：远程仓库恢复到最早的快照，并移除 pre-push hook 中的 yewpb 部分
+fn restore_original(git: &dyn GitBackend, dry_run: bool) -> Result<()> {
+    let Some(file) = list_snapshots(git)?.pop() else {
+        bail!("没有远程仓库快照，无法恢复到使用 yewpb 之前的状态");
+    };
+    let hook_installed = is_push_backup_hook_installed(git)?;
+    if dry_run {
+        let snapshot = file.load()?;
+        println!(
+            "[dry-run] 将恢复到 {} 的远程仓库（{}）: {}",
+            file.time(),
+            file.id,
+            snapshot.remote_names().join(", ")
+        );
+        if hook_installed {
+            println!("[dry-run] 将移除 pre-push hook 中的 yewpb 部分");
+        }
+        return Ok(());
+    }
+    restore_snapshot(git, &file)?;
+    // hook 中其他工具的内容保持不变
+    if hook_installed {
+        uninstall_pre_push_hook(git)?;
+        println!("已移除 pre-push hook 中的 yewpb 部分");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yewpb::git::fixtures::{self, A};
+    use yewpb::git::FakeGit;
+
+    #[test]
+    fn removes_unified_and_split_remotes() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote("origin", "https://origin.example.com/demo.git")
+            .with_remote(REMOTE_NAME, A)
+            .with_repo_config(MANAGED_PUSH_URLS, A)
+            .with_remote("a", A);
+
+        execute(
+            &git,
+            &fixtures::write_config(dir.path(), &fixtures::config()),
+            &ConfigSources::isolated(dir.path()),
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert!(git.remote(REMOTE_NAME).is_none());
+        assert!(git.repo_config(MANAGED_PUSH_URLS).is_empty());
+        assert!(git.remote("a").is_none());
+        assert!(git.remote("origin").is_some());
+    }
+
+    #[test]
+    fn keeps_same_named_remote_with_foreign_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote("a", "https://elsewhere.example.com/demo.git");
+
+        execute(
+            &git,
+            &fixtures::write_config(dir.path(), &fixtures::config()),
+            &ConfigSources::isolated(dir.path()),
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert!(git.remote("a").is_some());
+    }
+
+    #[test]
+    fn dry_run_keeps_unified_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote(REMOTE_NAME, A);
+
+        execute(
+            &git,
+            &fixtures::write_config(dir.path(), &fixtures::config()),
+            &ConfigSources::isolated(dir.path()),
+            true,
+            false,
+        )
+        .unwrap();
+
+        assert!(git.remote(REMOTE_NAME).is_some());
+    }
+
+    #[test]
+    fn restore_returns_to_the_oldest_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = "https://old.example.com/demo.git";
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote("a", original);
+        save_snapshot(&git, "apply").unwrap();
+        git.set_remote_url("a", A).unwrap();
+        git.add_remote(REMOTE_NAME, A).unwrap();
+        save_snapshot(&git, "apply").unwrap();
+
+        execute(
+            &git,
+            &fixtures::write_config(dir.path(), &fixtures::config()),
+            &ConfigSources::isolated(dir.path()),
+            false,
+            true,
+        )
+        .unwrap();
+
+        assert!(git.remote(REMOTE_NAME).is_none());
+        assert_eq!(git.remote("a").unwrap().0, original);
+    }
+
+    #[test]
+    fn restore_removes_yewpb_hook_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote("origin", "https://origin.example.com/demo.git");
+        save_snapshot(&git, "apply").unwrap();
+        let hook = dir.path().join("hooks").join("pre-push");
+        std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        std::fs::write(
+            &hook,
+            "#!/bin/sh
+echo lint
+",
+        )
+        .unwrap();
+        yewpb::git::install_pre_push_hook(&git).unwrap();
+
+        execute(
+            &git,
+            &fixtures::write_config(dir.path(), &fixtures::config()),
+            &ConfigSources::isolated(dir.path()),
+            true,
+            true,
+        )
+        .unwrap();
+        assert!(is_push_backup_hook_installed(&git).unwrap());
+
+        execute(
+            &git,
+            &fixtures::write_config(dir.path(), &fixtures::config()),
+            &ConfigSources::isolated(dir.path()),
+            false,
+            true,
+        )
+        .unwrap();
+        assert!(!is_push_backup_hook_installed(&git).unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&hook).unwrap(),
+            "#!/bin/sh\necho lint\n"
+        );
+    }
+
+    #[test]
+    fn restore_without_snapshot_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote(REMOTE_NAME, A);
+
+        assert!(execute(
+            &git,
+            &fixtures::write_config(dir.path(), &fixtures::config()),
+            &ConfigSources::isolated(dir.path()),
+            false,
+            true
+        )
+        .is_err());
+        assert!(git.remote(REMOTE_NAME).is_some());
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::{bail, Result};
+use std::path::Path;
+use yewpb::config::{
+    find_key, load_config, load_layered_config, load_merged_config, save_config, Defaults, Origin,
+    DEFAULT_KEYS,
+};
+
+/// 输出配置项最终生效的值，便于脚本读取
+pub fn execute_get(config_path: &Path, key: String) -> Result<()> {
+    let key = find_key(&key)?;
+    let layered = load_layered_config(config_path)?;
+    match layered.lookup(key.name, &Defaults::default()) {
+        Some((value, origin)) => {
+            println!("{}", value);
+            // 来源信息输出到 stderr，不影响脚本读取
+            let origin = match origin {
+                Origin::Env => format!("{} ({})", origin, key.env_var()),
+                _ => origin.to_string(),
+            };
+            eprintln!("来源: {}", origin);
+            Ok(())
+        }
+        None => bail!("配置项 '{}' 未设置", key.name),
+    }
+}
+
+/// 校验并写入配置项
+pub fn execute_set(config_path: &Path, key: String, value: String) -> Result<()> {
+    let key = find_key(&key)?;
+    let mut config = load_config(config_path)?;
+    config.defaults.set(key.name, &value)?;
+    if let (Some(group), "group") = (&config.defaults.group, key.name) {
+        // 分组也可能定义在项目配置中，按叠加后的配置检查
+        if !load_merged_config(config_path)?.groups.contains_key(group) {
+            println!("警告: 未找到分组 '{}'", group);
+        }
+    }
+    save_config(config_path, &config)?;
+    println!("已保存。");
+    Ok(())
+}
+
+/// 移除配置项
+pub fn execute_unset(config_path: &Path, key: String) -> Result<()> {
+    let key = find_key(&key)?;
+    let mut config = load_config(config_path)?;
+    if !config.defaults.unset(key.name)? {
+        println!("配置项 '{}' 未在用户配置中设置。", key.name);
+        return Ok(());
+    }
+    save_config(config_path, &config)?;
+    println!("已移除。");
+    Ok(())
+}
+
+/// 列出所有可用的配置项
+pub fn execute_keys() -> Result<()> {
+    let builtin = Defaults::builtin();
+    for key in DEFAULT_KEYS {
+        let default = builtin.get(key.name).unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<14} {:<18} 默认: {:<6} {}",
+            key.name,
+            key.kind.to_string(),
+            default,
+            key.description
+        );
+        println!("{:<14} 环境变量: {}", "", key.env_var());
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use console::style;
+use std::collections::BTreeMap;
+use yewpb::config::{Config, Remote, DEFAULT_KEYS};
+
+/// 变更统计
+#[derive(Debug, Default)]
+pub struct DiffSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    /// 默认配置与分组的变更数量
+    pub settings: usize,
+}
+
+impl DiffSummary {
+    pub fn is_empty(&self) -> bool {
+        self.added == 0 && self.updated == 0 && self.removed == 0 && self.settings == 0
+    }
+}
+
+/// 将远程仓库展开为 键 -> TOML 值 的映射，嵌套表使用点号连接
+pub fn flatten_remote(remote: &Remote) -> BTreeMap<String, String> {
+    let mut fields = BTreeMap::new();
+    if let Ok(toml::Value::Table(table)) = toml::Value::try_from(remote) {
+        flatten_table("", &table, &mut fields);
+    }
+    fields
+}
+
+fn flatten_table(prefix: &str, table: &toml::Table, fields: &mut BTreeMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            toml::Value::Table(inner) => flatten_table(&key, inner, fields),
+            value => {
+                fields.insert(key, value.to_string());
+            }
+        }
+    }
+}
+
+/// 两个远程仓库配置是否完全相同
+pub fn same_remote(a: &Remote, b: &Remote) -> bool {
+    flatten_remote(a) == flatten_remote(b)
+}
+
+/// 输出单个远程仓库的字段差异
+pub fn print_remote_changes(old: &Remote, new: &Remote, indent: &str) {
+    let old_fields = flatten_remote(old);
+    let new_fields = flatten_remote(new);
+    let keys: std::collections::BTreeSet<&String> =
+        old_fields.keys().chain(new_fields.keys()).collect();
+    for key in keys {
+        let (before, after) = (old_fields.get(key), new_fields.get(key));
+        if before == after {
+            continue;
+        }
+        if let Some(value) = before {
+            println!(
+                "{}{}",
+                indent,
+                style(format!("- {} = {}", key, value)).red()
+            );
+        }
+        if let Some(value) = after {
+            println!(
+                "{}{}",
+                indent,
+                style(format!("+ {} = {}", key, value)).green()
+            );
+        }
+    }
+}
+
+/// 输出两份配置之间的差异，返回变更统计
+pub fn print_config_diff(old: &Config, new: &Config) -> DiffSummary {
+    let mut summary = DiffSummary::default();
+
+    let mut remote_lines = Vec::new();
+    for remote in &new.remotes {
+        match old.remotes.iter().find(|r| r.name == remote.name) {
+            None => {
+                summary.added += 1;
+                remote_lines.push(RemoteLine::Added(remote));
+            }
+            Some(old_remote) if !same_remote(old_remote, remote) => {
+                summary.updated += 1;
+                remote_lines.push(RemoteLine::Updated(old_remote, remote));
+            }
+            Some(_) => {}
+        }
+    }
+    for remote in &old.remotes {
+        if !new.remotes.iter().any(|r| r.name == remote.name) {
+            summary.removed += 1;
+            remote_lines.push(RemoteLine::Removed(remote));
+        }
+    }
+    if !remote_lines.is_empty() {
+        println!("远程仓库:");
+        for line in remote_lines {
+            match line {
+                RemoteLine::Added(remote) => println!(
+                    "  {}",
+                    style(format!("+ {}\t{}", remote.name, remote.target())).green()
+                ),
+                RemoteLine::Removed(remote) => println!(
+                    "  {}",
+                    style(format!("- {}\t{}", remote.name, remote.target())).red()
+                ),
+                RemoteLine::Updated(old_remote, remote) => {
+                    println!("  {}", style(format!("~ {}", remote.name)).yellow());
+                    print_remote_changes(old_remote, remote, "      ");
+                }
+            }
+        }
+    }
+
+    let mut default_lines = Vec::new();
+    for key in DEFAULT_KEYS {
+        match (old.defaults.get(key.name), new.defaults.get(key.name)) {
+            (None, Some(value)) => default_lines.push(
+                style(format!("+ {} = {}", key.name, value))
+                    .green()
+                    .to_string(),
+            ),
+            (Some(value), None) => default_lines.push(
+                style(format!("- {} = {}", key.name, value))
+                    .red()
+                    .to_string(),
+            ),
+            (Some(before), Some(after)) if before != after => default_lines.push(
+                style(format!("~ {}: {} -> {}", key.name, before, after))
+                    .yellow()
+                    .to_string(),
+            ),
+            _ => {}
+        }
+    }
+    summary.settings += default_lines.len();
+    if !default_lines.is_empty() {
+        println!("默认配置:");
+        for line in &default_lines {
+            println!("  {}", line);
+        }
+    }
+
+    let mut group_lines = Vec::new();
+    for (name, members) in &new.groups {
+        match old.groups.get(name) {
+            None => group_lines.push(
+                style(format!("+ {}: {}", name, members.join(", ")))
+                    .green()
+                    .to_string(),
+            ),
+            Some(before) if before != members => group_lines.push(
+                style(format!(
+                    "~ {}: {} -> {}",
+                    name,
+                    before.join(", "),
+                    members.join(", ")
+                ))
+                .yellow()
+                .to_string(),
+            ),
+            Some(_) => {}
+        }
+    }
+    for (name, members) in &old.groups {
+        if !new.groups.contains_key(name) {
+            group_lines.push(
+                style(format!("- {}: {}", name, members.join(", ")))
+                    .red()
+                    .to_string(),
+            );
+        }
+    }
+    summary.settings += group_lines.len();
+    if !group_lines.is_empty() {
+        println!("分组:");
+        for line in &group_lines {
+            println!("  {}", line);
+        }
+    }
+
+    summary
+}
+
+enum RemoteLine<'a> {
+    Added(&'a Remote),
+    Removed(&'a Remote),
+    Updated(&'a Remote, &'a Remote),
+}
@@ -1,6 +1,9 @@
+use super::validate::print_report;
 use anyhow::{Context, Result};
 use std::fs;
+use std::io::{self, Write};
 use std::path::Path;
+use yewpb::config::{create_backup, validate_file};
 
 pub fn execute(config_path: &Path) -> Result<()> {
     if let Some(parent) = config_path.parent() {
@@ -14,6 +17,42 @@ pub fn execute(config_path: &Path) -> Result<()> {
             .with_context(|| format!("创建配置文件失败: {}", config_path.display()))?;
     }
 
-    edit::edit_file(config_path)?;
-    Ok(())
+    let original = fs::read_to_string(config_path)
+        .with_context(|| format!("读取配置文件失败: {}", config_path.display()))?;
+    let mut backed_up = false;
+    loop {
+        edit::edit_file(config_path)?;
+        if !backed_up {
+            backed_up = backup_if_changed(config_path, &original)?;
+        }
+
+        // 保存后立即校验，有错误时询问是否继续修改
+        let report = validate_file(config_path)?;
+        print_report(config_path.display(), &report);
+        if report.errors() == 0 {
+            return Ok(());
+        }
+
+        print!("配置文件存在错误，是否重新打开编辑器? (y/n) ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("已保留修改，请在使用前修正上述错误。");
+            return Ok(());
+        }
+    }
+}
+
+/// 编辑器直接修改文件，内容有变化时补充一份编辑前的备份，返回是否已备份
+fn backup_if_changed(config_path: &Path, original: &str) -> Result<bool> {
+    let current = fs::read_to_string(config_path)
+        .with_context(|| format!("读取配置文件失败: {}", config_path.display()))?;
+    if current == original {
+        return Ok(false);
+    }
+    if !original.trim().is_empty() {
+        create_backup(config_path, original)?;
+    }
+    Ok(true)
 }
@@ -1,20 +1,46 @@
-use crate::config::{load_config, save_config};
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use yewpb::config::{load_config, ConfigFormat};
 
-pub fn execute(config_path: &Path, output: Option<PathBuf>) -> Result<()> {
+pub fn execute(
+    config_path: &Path,
+    output: Option<PathBuf>,
+    format: Option<ConfigFormat>,
+) -> Result<()> {
     let config = load_config(config_path)?;
     if config.remotes.is_empty() {
-        println!("没有可导出的配置。");
+        eprintln!("没有可导出的配置。");
         return Ok(());
     }
 
-    let export_path = match output {
-        Some(path) => path,
-        None => PathBuf::from("yewpb-config.toml"),
+    // 未指定输出文件且标准输出被重定向时，直接输出到标准输出
+    let output = match output {
+        Some(path) => Some(path),
+        None if !io::stdout().is_terminal() => Some(PathBuf::from("-")),
+        None => None,
     };
 
-    save_config(&export_path, &config)?;
-    println!("配置已导出到: {}", export_path.display());
+    match output {
+        Some(path) if path.as_os_str() == "-" => {
+            let format = format.unwrap_or(ConfigFormat::Toml);
+            io::stdout().write_all(format.serialize(&config)?.as_bytes())?;
+        }
+        output => {
+            let format = format
+                .or_else(|| output.as_deref().and_then(ConfigFormat::from_path))
+                .unwrap_or(ConfigFormat::Toml);
+            let export_path = output
+                .unwrap_or_else(|| PathBuf::from(format!("yewpb-config.{}", format.extension())));
+            if let Some(parent) = export_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("创建目录失败: {}", parent.display()))?;
+            }
+            fs::write(&export_path, format.serialize(&config)?)
+                .with_context(|| format!("写入导出文件失败: {}", export_path.display()))?;
+            println!("配置已导出到: {}", export_path.display());
+        }
+    }
     Ok(())
 }
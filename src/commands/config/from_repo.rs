@@ -0,0 +1,60 @@
+use anyhow::Result;
+use std::path::Path;
+use yewpb::config::{Config, Remote};
+use yewpb::git::GitBackend;
+use yewpb::utils::base_from_url;
+use yewpb::REMOTE_NAME;
+
+/// 读取仓库中已有的远程地址，反推出待保存的远程仓库配置
+pub fn propose(git: &dyn GitBackend, dir: &Path, current: &Config) -> Result<Config> {
+    git.check_available()?;
+    let urls = git.remote_urls_in(dir)?;
+    println!("从 {} 读取到 {} 个远程地址。", dir.display(), urls.len());
+
+    let mut proposed = Config::default();
+    for (remote_name, url) in urls {
+        if remote_name == REMOTE_NAME {
+            continue;
+        }
+        let Some(base) = base_from_url(&url) else {
+            println!("- 跳过 {} ({})：无法识别基础地址", remote_name, url);
+            continue;
+        };
+
+        // 已有相同基础地址的配置无需重复添加
+        if let Some(existing) = current
+            .remotes
+            .iter()
+            .chain(&proposed.remotes)
+            .find(|r| r.base.trim_end_matches('/') == base.trim_end_matches('/'))
+        {
+            if existing.name != remote_name {
+                println!(
+                    "- 跳过 {} ({})：基础地址已存在于配置 '{}'",
+                    remote_name, url, existing.name
+                );
+            }
+            continue;
+        }
+
+        // 同一远程仓库有多个推送地址时，后续地址加序号区分
+        let name = unique_name(&remote_name, &proposed);
+        proposed.remotes.push(Remote {
+            name,
+            base,
+            ..Default::default()
+        });
+    }
+    Ok(proposed)
+}
+
+fn unique_name(name: &str, proposed: &Config) -> String {
+    let taken = |candidate: &str| proposed.remotes.iter().any(|r| r.name == candidate);
+    if !taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|i| format!("{}-{}", name, i))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use std::path::Path;
+use yewpb::config::{load_config, save_config};
+
+/// 创建或覆盖分组
+pub fn execute_set(config_path: &Path, name: String, remotes: Vec<String>) -> Result<()> {
+    let mut config = load_config(config_path)?;
+    for remote in &remotes {
+        if !config.remotes.iter().any(|r| &r.name == remote) {
+            println!("警告: 远程仓库 '{}' 不在配置中", remote);
+        }
+    }
+    config.groups.insert(name, remotes);
+    save_config(config_path, &config)?;
+    println!("已保存。");
+    Ok(())
+}
+
+/// 移除分组
+pub fn execute_remove(config_path: &Path, name: String) -> Result<()> {
+    let mut config = load_config(config_path)?;
+    if config.groups.remove(&name).is_none() {
+        println!("未找到匹配的分组。");
+        return Ok(());
+    }
+    if config.defaults.group.as_deref() == Some(name.as_str()) {
+        config.defaults.group = None;
+        println!("已同时清除默认分组设置。");
+    }
+    save_config(config_path, &config)?;
+    println!("已移除。");
+    Ok(())
+}
+
+/// 列出所有分组
+pub fn execute_list(config_path: &Path) -> Result<()> {
+    let config = load_config(config_path)?;
+    if config.groups.is_empty() {
+        println!("没有配置分组。");
+        return Ok(());
+    }
+    for (name, remotes) in &config.groups {
+        let marker = if config.defaults.group.as_deref() == Some(name.as_str()) {
+            " (默认)"
+        } else {
+            ""
+        };
+        println!("{}{}\t{}", name, marker, remotes.join(", "));
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use yewpb::config::{find_backup, list_backups, save_config_content, MAX_BACKUPS};
+
+/// 列出所有备份，最新的在前
+pub fn execute_history(config_path: &Path) -> Result<()> {
+    let backups = list_backups(config_path)?;
+    if backups.is_empty() {
+        println!("没有配置备份。");
+        return Ok(());
+    }
+    println!("配置备份（最多保留 {} 个，最新的在前）:", MAX_BACKUPS);
+    for backup in &backups {
+        let remotes = match backup.remote_count() {
+            Some(count) => format!("{} 个远程仓库", count),
+            None => "无法解析".to_string(),
+        };
+        println!("  {:<20} {}  {}", backup.id, backup.time(), remotes);
+    }
+    println!();
+    println!(
+        "使用 yewpb config restore <编号> 恢复指定备份，或 yewpb config undo 恢复最近一次备份。"
+    );
+    Ok(())
+}
+
+/// 恢复最近一次备份
+pub fn execute_undo(config_path: &Path) -> Result<()> {
+    match list_backups(config_path)?.into_iter().next() {
+        Some(backup) => restore(config_path, &backup.id),
+        None => {
+            println!("没有可恢复的配置备份。");
+            Ok(())
+        }
+    }
+}
+
+/// 恢复指定编号的备份
+pub fn execute_restore(config_path: &Path, id: String) -> Result<()> {
+    restore(config_path, &id)
+}
+
+fn restore(config_path: &Path, id: &str) -> Result<()> {
+    let backup = find_backup(config_path, id)?;
+    let content = fs::read_to_string(&backup.path)
+        .with_context(|| format!("读取备份失败: {}", backup.path.display()))?;
+    // 恢复前的配置同样会被备份，可以再次 undo 撤销本次恢复
+    save_config_content(config_path, &content)?;
+    println!("已恢复到 {} 的配置（{}）。", backup.time(), backup.id);
+    Ok(())
+}
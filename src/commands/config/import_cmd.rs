@@ -1,6 +1,15 @@
-use crate::config::{load_config, save_config, Config};
-use anyhow::{bail, Result};
+use super::diff::{print_config_diff, print_remote_changes, same_remote};
+use super::from_repo;
+use super::validate::print_report;
+use crate::cli::OnConflict;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use yewpb::config::{
+    load_config, save_config, validate_str, Config, ConfigFormat, FileKind, DEFAULT_KEYS,
+};
+use yewpb::git::GitBackend;
 
 /// 候选配置文件名
 const CANDIDATE_FILES: &[&str] = &["config.toml", "yewpb.toml"];
@@ -14,87 +23,281 @@ fn find_candidate_files() -> Vec<PathBuf> {
         .collect()
 }
 
-pub fn execute(config_path: &Path, input: Option<PathBuf>, merge: bool) -> Result<()> {
-    let input = match input {
-        Some(path) => path,
-        None => {
-            let candidates = find_candidate_files();
-            if candidates.is_empty() {
-                println!("未指定导入文件。");
-                println!();
-                println!("用法: pb config import -i <文件路径>");
-                println!();
-                println!(
-                    "提示: 当前目录下未找到 {} 文件。",
-                    CANDIDATE_FILES.join(" 或 ")
-                );
-                return Ok(());
-            } else if candidates.len() == 1 {
-                let file = &candidates[0];
-                println!(
-                    "提示: 检测到当前目录存在 {}，你是想执行以下命令吗？",
-                    file.display()
-                );
-                println!();
-                println!("  pb config import -i {}", file.display());
-                if merge {
-                    println!("  pb config import -i {} --merge", file.display());
-                }
-                return Ok(());
-            } else {
-                println!("未指定导入文件，但检测到当前目录存在以下候选文件：");
-                println!();
-                for file in &candidates {
-                    println!("  pb config import -i {}", file.display());
-                }
-                println!();
-                println!("请选择一个文件进行导入。");
-                return Ok(());
+/// 读取导入内容，- 表示标准输入
+fn read_input(input: &Path) -> Result<String> {
+    if input.as_os_str() == "-" {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .context("读取标准输入失败")?;
+        return Ok(content);
+    }
+    if !input.exists() {
+        bail!("导入文件不存在: {}", input.display());
+    }
+    fs::read_to_string(input).with_context(|| format!("读取导入文件失败: {}", input.display()))
+}
+
+/// 解析并校验导入内容，有错误时拒绝导入
+fn parse_checked(content: &str, format: ConfigFormat, label: &Path) -> Result<Config> {
+    let config = format
+        .parse(content)
+        .with_context(|| format!("无法解析导入文件: {}", label.display()))?;
+
+    // 校验基于 TOML，其他格式先转换，行号不再对应原文件
+    let report = match format {
+        ConfigFormat::Toml => validate_str(content, FileKind::Global),
+        _ => {
+            let mut report =
+                validate_str(&ConfigFormat::Toml.serialize(&config)?, FileKind::Global);
+            for diagnostic in &mut report.diagnostics {
+                diagnostic.line = None;
             }
+            report
         }
     };
-
-    if !input.exists() {
-        bail!("导入文件不存在: {}", input.display());
+    if !report.diagnostics.is_empty() {
+        print_report(label.display(), &report);
+    }
+    if report.errors() > 0 {
+        bail!("导入文件存在错误，已取消导入");
     }
+    Ok(config)
+}
+
+/// 导入命令的参数
+pub struct ImportArgs {
+    pub input: Option<PathBuf>,
+    pub merge: bool,
+    pub format: Option<ConfigFormat>,
+    pub dry_run: bool,
+    pub on_conflict: OnConflict,
+    /// 从已有 git 仓库的远程地址生成配置
+    pub from_repo: Option<PathBuf>,
+    pub yes: bool,
+}
 
-    let import_config = load_config(&input)?;
+pub fn execute(git: &dyn GitBackend, config_path: &Path, args: ImportArgs) -> Result<()> {
+    let current = load_config(config_path)?;
+
+    // 从仓库生成的配置总是合并到当前配置
+    let (import_config, merge, from_stdin) = match &args.from_repo {
+        Some(dir) => (from_repo::propose(git, dir, &current)?, true, false),
+        None => {
+            let Some(input) = resolve_input(args.input, args.merge) else {
+                return Ok(());
+            };
+            // 格式优先级：--format > 扩展名 > 内容推断
+            let content = read_input(&input)?;
+            let format = args
+                .format
+                .or_else(|| ConfigFormat::from_path(&input))
+                .unwrap_or_else(|| ConfigFormat::detect(&content));
+            let from_stdin = input.as_os_str() == "-";
+            let label = if from_stdin {
+                PathBuf::from("标准输入")
+            } else {
+                input
+            };
+            // 导入前先校验，避免把有问题的配置写入用户配置
+            let config = parse_checked(&content, format, &label)?;
+            (config, args.merge, from_stdin)
+        }
+    };
     if import_config.remotes.is_empty() {
-        println!("导入文件中没有配置。");
+        println!("没有可导入的配置。");
         return Ok(());
     }
+    let on_conflict = args.on_conflict;
+    let dry_run = args.dry_run;
+    if on_conflict == OnConflict::Ask && !dry_run && from_stdin {
+        bail!("从标准输入导入时无法逐项询问，请使用 --on-conflict keep 或 theirs");
+    }
 
-    let mut config = if merge {
-        load_config(config_path)?
+    let config = if merge {
+        // 预览时不询问，冲突项按导入文件展示
+        let strategy = match on_conflict {
+            OnConflict::Ask if dry_run => OnConflict::Theirs,
+            strategy => strategy,
+        };
+        merge_config(&current, import_config, strategy)?
     } else {
-        Config::default()
+        import_config
     };
 
-    let mut added = 0;
-    let mut updated = 0;
-
-    for import_remote in import_config.remotes {
-        let mut found = false;
-        for remote in &mut config.remotes {
-            if remote.name == import_remote.name {
-                remote.base = import_remote.base.clone();
-                updated += 1;
-                found = true;
-                break;
-            }
+    let summary = print_config_diff(&current, &config);
+    if summary.is_empty() {
+        println!("导入的配置与当前配置相同，无需修改。");
+        return Ok(());
+    }
+    println!();
+    let counts = format!(
+        "新增 {} 个，更新 {} 个，移除 {} 个远程仓库",
+        summary.added, summary.updated, summary.removed
+    );
+    if dry_run {
+        if merge && on_conflict == OnConflict::Ask {
+            println!("预览中冲突项按导入的配置显示，实际导入时将逐项询问。");
         }
-        if !found {
-            config.remotes.push(import_remote);
-            added += 1;
+        println!("预览: {}（未写入配置）。", counts);
+        return Ok(());
+    }
+
+    // 从仓库推断的配置需要确认后再保存
+    if args.from_repo.is_some() && !args.yes {
+        print!("保存以上配置? (y/n) ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("操作已取消。");
+            return Ok(());
         }
     }
 
+    let had_config = config_path.exists();
     save_config(config_path, &config)?;
-
     if merge {
-        println!("配置已合并: 新增 {} 个，更新 {} 个。", added, updated);
+        println!("配置已合并: {}。", counts);
     } else {
-        println!("配置已导入: {} 个远程仓库。", added);
+        println!("配置已导入: {}。", counts);
+    }
+    if had_config {
+        println!("原配置已备份，可使用 yewpb config undo 恢复。");
     }
     Ok(())
 }
+
+/// 确定导入文件，未指定时提示当前目录下的候选文件并返回 None
+fn resolve_input(input: Option<PathBuf>, merge: bool) -> Option<PathBuf> {
+    if input.is_some() {
+        return input;
+    }
+    let candidates = find_candidate_files();
+    if candidates.is_empty() {
+        println!("未指定导入文件。");
+        println!();
+        println!("用法: pb config import -i <文件路径>");
+        println!();
+        println!(
+            "提示: 当前目录下未找到 {} 文件。",
+            CANDIDATE_FILES.join(" 或 ")
+        );
+    } else if candidates.len() == 1 {
+        let file = &candidates[0];
+        println!(
+            "提示: 检测到当前目录存在 {}，你是想执行以下命令吗？",
+            file.display()
+        );
+        println!();
+        println!("  pb config import -i {}", file.display());
+        if merge {
+            println!("  pb config import -i {} --merge", file.display());
+        }
+    } else {
+        println!("未指定导入文件，但检测到当前目录存在以下候选文件：");
+        println!();
+        for file in &candidates {
+            println!("  pb config import -i {}", file.display());
+        }
+        println!();
+        println!("请选择一个文件进行导入。");
+    }
+    None
+}
+
+/// 冲突时采用哪一方
+enum Choice {
+    Keep,
+    Theirs,
+}
+
+/// 按策略解决冲突，ask 时交互询问
+fn resolve(strategy: OnConflict, describe: impl Fn()) -> Result<Choice> {
+    match strategy {
+        OnConflict::Keep => Ok(Choice::Keep),
+        OnConflict::Theirs => Ok(Choice::Theirs),
+        OnConflict::Ask => {
+            describe();
+            loop {
+                print!("  保留当前配置 (k) 还是使用导入的配置 (t)? [k/t] ");
+                io::stdout().flush()?;
+                let mut input = String::new();
+                if io::stdin().read_line(&mut input)? == 0 {
+                    bail!("输入已结束，导入已取消");
+                }
+                match input.trim().to_ascii_lowercase().as_str() {
+                    "k" | "keep" => return Ok(Choice::Keep),
+                    "t" | "theirs" => return Ok(Choice::Theirs),
+                    _ => continue,
+                }
+            }
+        }
+    }
+}
+
+/// 将导入的配置合并到当前配置：新条目直接加入，不同的同名条目按策略解决
+fn merge_config(current: &Config, incoming: Config, strategy: OnConflict) -> Result<Config> {
+    let mut config = current.clone();
+
+    for remote in incoming.remotes {
+        match config.remotes.iter_mut().find(|r| r.name == remote.name) {
+            None => config.remotes.push(remote),
+            Some(existing) if same_remote(existing, &remote) => {}
+            Some(existing) => {
+                let choice = resolve(strategy, || {
+                    println!("远程仓库 '{}' 存在冲突:", remote.name);
+                    print_remote_changes(existing, &remote, "    ");
+                })?;
+                if let Choice::Theirs = choice {
+                    *existing = remote;
+                }
+            }
+        }
+    }
+
+    for key in DEFAULT_KEYS {
+        let Some(theirs) = incoming.defaults.get(key.name) else {
+            continue;
+        };
+        match config.defaults.get(key.name) {
+            Some(ours) if ours == theirs => {}
+            Some(ours) => {
+                let choice = resolve(strategy, || {
+                    println!(
+                        "默认配置 '{}' 存在冲突: 当前 {}，导入 {}",
+                        key.name, ours, theirs
+                    );
+                })?;
+                if let Choice::Theirs = choice {
+                    config.defaults.set(key.name, &theirs)?;
+                }
+            }
+            None => config.defaults.set(key.name, &theirs)?,
+        }
+    }
+
+    for (name, members) in incoming.groups {
+        match config.groups.get(&name) {
+            Some(ours) if *ours == members => {}
+            Some(ours) => {
+                let choice = resolve(strategy, || {
+                    println!(
+                        "分组 '{}' 存在冲突: 当前 {}，导入 {}",
+                        name,
+                        ours.join(", "),
+                        members.join(", ")
+                    );
+                })?;
+                if let Choice::Theirs = choice {
+                    config.groups.insert(name, members);
+                }
+            }
+            None => {
+                config.groups.insert(name, members);
+            }
+        }
+    }
+
+    Ok(config)
+}
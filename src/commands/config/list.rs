@@ -1,6 +1,7 @@
-use crate::config::load_config;
 use anyhow::Result;
 use std::path::Path;
+use yewpb::config::{load_config, Remote};
+use yewpb::rename::RenameRule;
 
 /// 截断字符串，超过指定长度时添加省略号
 fn truncate_note(note: &str, max_len: usize) -> String {
@@ -11,6 +12,40 @@ fn truncate_note(note: &str, max_len: usize) -> String {
     }
 }
 
+/// 禁用与拉取地址标记
+fn state_mark(remote: &Remote) -> &'static str {
+    match (remote.enabled, remote.primary) {
+        (false, _) => " (已禁用)",
+        (true, true) => " (拉取)",
+        (true, false) => "",
+    }
+}
+
+/// 将仓库名称映射规则格式化为一行说明
+fn describe_rename(rule: &RenameRule) -> String {
+    if let Some(name) = &rule.name {
+        return format!("固定为 {}", name);
+    }
+    let mut parts = Vec::new();
+    if let Some(pattern) = &rule.pattern {
+        parts.push(format!(
+            "替换 /{}/ 为 \"{}\"",
+            pattern,
+            rule.replace.as_deref().unwrap_or("")
+        ));
+    }
+    if let Some(case) = rule.case {
+        parts.push(format!("{:?}", case).to_lowercase());
+    }
+    if let Some(prefix) = &rule.prefix {
+        parts.push(format!("前缀 {}", prefix));
+    }
+    if let Some(suffix) = &rule.suffix {
+        parts.push(format!("后缀 {}", suffix));
+    }
+    parts.join("，")
+}
+
 pub fn execute(config_path: &Path, long: bool) -> Result<()> {
     let config = load_config(config_path)?;
     if config.remotes.is_empty() {
@@ -23,8 +58,43 @@ pub fn execute(config_path: &Path, long: bool) -> Result<()> {
         println!("=== 所有远程仓库配置 ===");
         println!("共 {} 个配置\n", config.remotes.len());
         for (i, remote) in config.remotes.iter().enumerate() {
-            println!("[{}] {}", i + 1, remote.name);
-            println!("    基础地址: {}", remote.base);
+            println!("[{}] {}{}", i + 1, remote.name, state_mark(remote));
+            if !remote.base.is_empty() {
+                println!("    基础地址: {}", remote.base);
+            }
+            if let Some(template) = &remote.template {
+                println!("    URL 模板: {}", template);
+            }
+            if !remote.vars.is_empty() {
+                let vars: Vec<String> = remote
+                    .vars
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                println!("    模板变量: {}", vars.join(", "));
+            }
+            if !remote.rename.is_empty() {
+                println!("    仓库名称规则: {}", describe_rename(&remote.rename));
+            }
+            if let Some(ssh_key) = &remote.ssh_key {
+                println!("    SSH 私钥: {}", ssh_key);
+            }
+            if let Some(ssh_command) = &remote.ssh_command {
+                println!("    SSH 命令: {}", ssh_command);
+            }
+            if !remote.git_config.is_empty() {
+                let config: Vec<String> = remote
+                    .git_config
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                println!("    git 配置: {}", config.join(", "));
+            }
+            if remote.no_proxy {
+                println!("    代理: 不使用");
+            } else if let Some(proxy) = &remote.proxy {
+                println!("    代理: {}", proxy);
+            }
             if let Some(note) = &remote.note {
                 println!("    备注: {}", note);
             }
@@ -34,12 +104,13 @@ pub fn execute(config_path: &Path, long: bool) -> Result<()> {
         }
     } else {
         // 简洁模式：截断 note
-        for remote in config.remotes {
-            if let Some(note) = remote.note {
-                let truncated = truncate_note(&note, 20);
-                println!("{}\t{}\t# {}", remote.name, remote.base, truncated);
+        for remote in &config.remotes {
+            let name = format!("{}{}", remote.name, state_mark(remote));
+            if let Some(note) = &remote.note {
+                let truncated = truncate_note(note, 20);
+                println!("{}\t{}\t# {}", name, remote.target(), truncated);
             } else {
-                println!("{}\t{}", remote.name, remote.base);
+                println!("{}\t{}", name, remote.target());
             }
         }
     }
@@ -1,22 +1,97 @@
+mod defaults;
+mod diff;
 mod edit;
 mod export;
+mod from_repo;
+mod group;
+mod history;
 mod import_cmd;
 mod list;
+mod order;
 mod remove;
 mod set;
+mod show;
+mod state;
+mod validate;
 
-use crate::cli::ConfigCommands;
+use crate::cli::{ConfigCommands, DefaultsCommands, GroupCommands};
 use anyhow::Result;
+use import_cmd::ImportArgs;
+use set::SetArgs;
 use std::path::Path;
+use yewpb::git::GitBackend;
 
 /// 配置子命令的统一分发入口
-pub fn execute(config_path: &Path, cmd: ConfigCommands) -> Result<()> {
+pub fn execute(git: &dyn GitBackend, config_path: &Path, cmd: ConfigCommands) -> Result<()> {
     match cmd {
-        ConfigCommands::Set { name, base, note } => set::execute(config_path, name, base, note),
+        ConfigCommands::Set {
+            name,
+            base,
+            template,
+            vars,
+            rename,
+            policy,
+            note,
+        } => set::execute(
+            config_path,
+            name,
+            SetArgs {
+                base,
+                template,
+                vars,
+                rename,
+                policy,
+                note,
+            },
+        ),
         ConfigCommands::Remove { name } => remove::execute(config_path, name),
+        ConfigCommands::Enable { name } => state::execute(config_path, name, true),
+        ConfigCommands::Disable { name } => state::execute(config_path, name, false),
+        ConfigCommands::SetPrimary { name } => order::execute_set_primary(config_path, name),
+        ConfigCommands::Move {
+            name,
+            before,
+            after,
+        } => order::execute_move(config_path, name, before, after),
         ConfigCommands::List { long } => list::execute(config_path, long),
-        ConfigCommands::Export { output } => export::execute(config_path, output),
-        ConfigCommands::Import { input, merge } => import_cmd::execute(config_path, input, merge),
+        ConfigCommands::Export { output, format } => export::execute(config_path, output, format),
+        ConfigCommands::Import {
+            input,
+            merge,
+            format,
+            dry_run,
+            on_conflict,
+            from_repo,
+            yes,
+        } => import_cmd::execute(
+            git,
+            config_path,
+            ImportArgs {
+                input,
+                merge,
+                format,
+                dry_run,
+                on_conflict,
+                from_repo,
+                yes,
+            },
+        ),
         ConfigCommands::Edit => edit::execute(config_path),
+        ConfigCommands::History => history::execute_history(config_path),
+        ConfigCommands::Undo => history::execute_undo(config_path),
+        ConfigCommands::Restore { id } => history::execute_restore(config_path, id),
+        ConfigCommands::Validate { path } => validate::execute(config_path, path),
+        ConfigCommands::Show { origin } => show::execute(config_path, origin),
+        ConfigCommands::Group { command } => match command {
+            GroupCommands::Set { name, remotes } => group::execute_set(config_path, name, remotes),
+            GroupCommands::Remove { name } => group::execute_remove(config_path, name),
+            GroupCommands::List => group::execute_list(config_path),
+        },
+        ConfigCommands::Defaults { command } => match command {
+            DefaultsCommands::Get { key } => defaults::execute_get(config_path, key),
+            DefaultsCommands::Set { key, value } => defaults::execute_set(config_path, key, value),
+            DefaultsCommands::Unset { key } => defaults::execute_unset(config_path, key),
+            DefaultsCommands::Keys => defaults::execute_keys(),
+        },
     }
 }
@@ -0,0 +1,62 @@
+use anyhow::{bail, Result};
+use std::path::Path;
+use yewpb::config::{load_config, save_config};
+
+/// 将远程仓库设为拉取地址，同时取消其他仓库的 primary 标记
+pub fn execute_set_primary(config_path: &Path, name: String) -> Result<()> {
+    let mut config = load_config(config_path)?;
+    if !config.remotes.iter().any(|r| r.name == name) {
+        println!("未找到匹配的远程仓库。");
+        return Ok(());
+    }
+    for remote in &mut config.remotes {
+        remote.primary = remote.name == name;
+    }
+    save_config(config_path, &config)?;
+    println!("已将 '{}' 设为拉取地址，重新运行 apply 后生效。", name);
+    if let Some(fetch_remote) = config
+        .defaults
+        .fetch_remote
+        .as_ref()
+        .filter(|r| **r != name)
+    {
+        println!(
+            "注意: defaults.fetch_remote = \"{}\" 优先于 primary 标记，可运行 yewpb config defaults unset fetch_remote 清除。",
+            fetch_remote
+        );
+    }
+    Ok(())
+}
+
+/// 调整远程仓库在配置中的顺序
+pub fn execute_move(
+    config_path: &Path,
+    name: String,
+    before: Option<String>,
+    after: Option<String>,
+) -> Result<()> {
+    let mut config = load_config(config_path)?;
+    let (target, place_after) = match (before, after) {
+        (Some(target), None) => (target, false),
+        (None, Some(target)) => (target, true),
+        _ => bail!("请指定 --before 或 --after 其中之一"),
+    };
+    if target == name {
+        bail!("不能相对于自身移动");
+    }
+
+    let Some(from) = config.remotes.iter().position(|r| r.name == name) else {
+        bail!("未找到远程仓库 '{}'", name);
+    };
+    let remote = config.remotes.remove(from);
+    let Some(to) = config.remotes.iter().position(|r| r.name == target) else {
+        bail!("未找到远程仓库 '{}'", target);
+    };
+    let to = if place_after { to + 1 } else { to };
+    config.remotes.insert(to, remote);
+
+    save_config(config_path, &config)?;
+    let names: Vec<&str> = config.remotes.iter().map(|r| r.name.as_str()).collect();
+    println!("已调整顺序: {}", names.join(", "));
+    Ok(())
+}
@@ -1,6 +1,6 @@
-use crate::config::{load_config, save_config};
 use anyhow::Result;
 use std::path::Path;
+use yewpb::config::{load_config, save_config};
 
 pub fn execute(config_path: &Path, name: String) -> Result<()> {
     let mut config = load_config(config_path)?;
@@ -10,6 +10,10 @@ pub fn execute(config_path: &Path, name: String) -> Result<()> {
         println!("未找到匹配的远程仓库。");
         return Ok(());
     }
+    // 同时从所有分组中移除
+    for members in config.groups.values_mut() {
+        members.retain(|member| member != &name);
+    }
     save_config(config_path, &config)?;
     println!("已移除。");
     Ok(())
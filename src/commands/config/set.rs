@@ -1,23 +1,138 @@
-use crate::config::{load_config, save_config, Remote};
-use anyhow::Result;
+use crate::cli::{PolicyArgs, RenameArgs};
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
 use std::path::Path;
+use yewpb::config::{load_config, save_config, Remote};
+use yewpb::rename::RenameRule;
+use yewpb::template;
+
+/// config set 的可选参数
+pub struct SetArgs {
+    pub base: Option<String>,
+    pub template: Option<String>,
+    pub vars: Vec<String>,
+    pub rename: RenameArgs,
+    pub policy: PolicyArgs,
+    pub note: Option<String>,
+}
+
+/// 解析 KEY=VALUE 形式的模板变量
+fn parse_vars(vars: &[String]) -> Result<BTreeMap<String, String>> {
+    let mut parsed = BTreeMap::new();
+    for var in vars {
+        match var.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                let key = key.trim();
+                if !template::VAR_PLACEHOLDERS.contains(&key) {
+                    bail!(
+                        "未知的模板变量 '{}'，可用变量: {}",
+                        key,
+                        template::VAR_PLACEHOLDERS.join(", ")
+                    );
+                }
+                parsed.insert(key.to_string(), value.trim().to_string());
+            }
+            _ => bail!("模板变量格式应为 KEY=VALUE: {}", var),
+        }
+    }
+    Ok(parsed)
+}
+
+/// 将命令行参数合并到已有的映射规则上
+fn merge_rename(rule: &mut RenameRule, args: RenameArgs) -> Result<()> {
+    if args.clear_rename {
+        *rule = RenameRule::default();
+    }
+    if args.repo_name.is_some() {
+        rule.name = args.repo_name;
+    }
+    if args.repo_prefix.is_some() {
+        rule.prefix = args.repo_prefix;
+    }
+    if args.repo_suffix.is_some() {
+        rule.suffix = args.repo_suffix;
+    }
+    if args.repo_case.is_some() {
+        rule.case = args.repo_case;
+    }
+    if let [pattern, replace] = args.repo_replace.as_slice() {
+        rule.pattern = Some(pattern.clone());
+        rule.replace = Some(replace.clone());
+    }
+    // 提前校验正则表达式，避免保存无效规则
+    rule.validate()
+}
+
+/// 将命令行参数合并到远程仓库的推送策略上
+fn merge_policy(remote: &mut Remote, args: PolicyArgs) {
+    if args.clear_policy {
+        remote.timeout = None;
+        remote.retry = None;
+        remote.retry_delay = None;
+        remote.allow_force = None;
+        remote.extra_args.clear();
+        remote.skip_check = None;
+    }
+    if args.timeout.is_some() {
+        remote.timeout = args.timeout;
+    }
+    if args.retry.is_some() {
+        remote.retry = args.retry;
+    }
+    if args.retry_delay.is_some() {
+        remote.retry_delay = args.retry_delay;
+    }
+    if args.allow_force.is_some() {
+        remote.allow_force = args.allow_force;
+    }
+    if !args.extra_args.is_empty() {
+        // 与 push --git-args 一致，使用 shlex 解析引号包裹的参数
+        remote.extra_args = args
+            .extra_args
+            .iter()
+            .flat_map(|s| shlex::split(s).unwrap_or_else(|| vec![s.clone()]))
+            .collect();
+    }
+    if args.skip_check.is_some() {
+        remote.skip_check = args.skip_check;
+    }
+}
+
+pub fn execute(config_path: &Path, name: String, args: SetArgs) -> Result<()> {
+    if let Some(t) = &args.template {
+        template::validate(t)?;
+    }
+    let vars = parse_vars(&args.vars)?;
 
-pub fn execute(config_path: &Path, name: String, base: String, note: Option<String>) -> Result<()> {
     let mut config = load_config(config_path)?;
-    let mut updated = false;
-    for remote in &mut config.remotes {
-        if remote.name == name {
-            remote.base = base.clone();
-            if let Some(n) = &note {
-                remote.note = Some(n.clone());
+    let index = match config.remotes.iter().position(|remote| remote.name == name) {
+        Some(index) => index,
+        None => {
+            if args.base.is_none() && args.template.is_none() {
+                bail!("新增远程仓库时必须提供 base 或 --template");
             }
-            updated = true;
-            break;
+            config.remotes.push(Remote {
+                name,
+                ..Default::default()
+            });
+            config.remotes.len() - 1
         }
+    };
+
+    let remote = &mut config.remotes[index];
+    if let Some(base) = args.base {
+        remote.base = base;
+    }
+    if args.template.is_some() {
+        remote.template = args.template;
     }
-    if !updated {
-        config.remotes.push(Remote { name, base, note });
+    remote.vars.extend(vars);
+    merge_rename(&mut remote.rename, args.rename)?;
+    merge_policy(remote, args.policy);
+    if args.note.is_some() {
+        remote.note = args.note;
     }
+
     save_config(config_path, &config)?;
     println!("已保存。");
     Ok(())
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use std::path::Path;
+use yewpb::config::{load_layered_config, system_config_path, Defaults, Origin, DEFAULT_KEYS};
+
+pub fn execute(config_path: &Path, show_origin: bool) -> Result<()> {
+    let layered = load_layered_config(config_path)?;
+    let cli = Defaults::default();
+
+    println!("=== 配置文件 ===");
+    if let Some(path) = system_config_path() {
+        println!("  系统配置: {}{}", path.display(), missing_mark(&path));
+    }
+    println!(
+        "  用户配置: {}{}",
+        config_path.display(),
+        missing_mark(config_path)
+    );
+
+    println!("\n=== 默认配置 ===");
+    for key in DEFAULT_KEYS {
+        match layered.lookup(key.name, &cli) {
+            Some((value, origin)) if show_origin => {
+                // 环境变量来源同时显示变量名，便于定位
+                let origin = match origin {
+                    Origin::Env => format!("{} ({})", origin, key.env_var()),
+                    _ => origin.to_string(),
+                };
+                println!("  {:<14} = {:<12} # {}", key.name, value, origin);
+            }
+            Some((value, _)) => println!("  {:<14} = {}", key.name, value),
+            None => println!("  {:<14} (未设置)", key.name),
+        }
+    }
+
+    let config = &layered.config;
+    println!("\n=== 远程仓库 ===");
+    if config.remotes.is_empty() {
+        println!("  (无)");
+    }
+    for remote in &config.remotes {
+        match layered.remote_origins.get(&remote.name) {
+            Some(origin) if show_origin => {
+                println!("  {:<14} {:<40} # {}", remote.name, remote.target(), origin)
+            }
+            _ => println!("  {:<14} {}", remote.name, remote.target()),
+        }
+    }
+    for disabled in &config.disabled {
+        println!("  {:<14} ({})", disabled.remote.name, disabled.reason);
+    }
+
+    if !config.groups.is_empty() {
+        println!("\n=== 分组 ===");
+        for (name, members) in &config.groups {
+            println!("  {:<14} {}", name, members.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+/// 文件不存在时的提示
+fn missing_mark(path: &Path) -> &'static str {
+    if path.exists() {
+        ""
+    } else {
+        "（不存在）"
+    }
+}
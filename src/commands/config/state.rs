@@ -0,0 +1,31 @@
+use anyhow::Result;
+use std::path::Path;
+use yewpb::config::{load_config, save_config};
+
+/// 启用或禁用远程仓库
+pub fn execute(config_path: &Path, name: String, enabled: bool) -> Result<()> {
+    let mut config = load_config(config_path)?;
+    let Some(remote) = config.remotes.iter_mut().find(|r| r.name == name) else {
+        println!("未找到匹配的远程仓库。");
+        return Ok(());
+    };
+    if remote.enabled == enabled {
+        println!(
+            "远程仓库 '{}' 已经是{}状态。",
+            name,
+            if enabled { "启用" } else { "禁用" }
+        );
+        return Ok(());
+    }
+    remote.enabled = enabled;
+    save_config(config_path, &config)?;
+    if enabled {
+        println!("已启用 '{}'。", name);
+    } else {
+        println!(
+            "已禁用 '{}'，使用 yewpb config enable {} 重新启用。",
+            name, name
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anyhow::{bail, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use yewpb::config::{
+    find_repo_root, load_layered_config, system_config_path, validate_file, validate_layered,
+    Report, REPO_CONFIG_FILE, REPO_LOCAL_CONFIG_FILE,
+};
+
+/// 校验指定的配置文件；未指定时校验所有存在的配置层
+pub fn execute(config_path: &Path, path: Option<PathBuf>) -> Result<()> {
+    let layered = path.is_none();
+    let files = match path {
+        Some(path) => {
+            if !path.exists() {
+                bail!("配置文件不存在: {}", path.display());
+            }
+            vec![path]
+        }
+        None => existing_config_files(config_path),
+    };
+    if files.is_empty() {
+        println!("没有找到任何配置文件。");
+        return Ok(());
+    }
+
+    let mut errors = 0;
+    for file in &files {
+        let report = validate_file(file)?;
+        print_report(file.display(), &report);
+        errors += report.errors();
+    }
+    // 各文件单独合法时，再检查叠加后才会出现的冲突
+    if layered && files.len() > 1 && errors == 0 {
+        let report = validate_layered(&load_layered_config(config_path)?);
+        print_report("合并后的配置", &report);
+        errors += report.errors();
+    }
+    if errors > 0 {
+        bail!("配置校验失败，共 {} 个错误", errors);
+    }
+    Ok(())
+}
+
+/// 输出一个配置文件的校验结果
+pub fn print_report(label: impl fmt::Display, report: &Report) {
+    if report.diagnostics.is_empty() {
+        println!("✓ {}", label);
+        return;
+    }
+    let mark = if report.errors() > 0 { "✗" } else { "!" };
+    println!(
+        "{} {}（{} 个错误，{} 个警告）",
+        mark,
+        label,
+        report.errors(),
+        report.warnings()
+    );
+    for diagnostic in &report.diagnostics {
+        println!("  {}", diagnostic);
+    }
+}
+
+/// 按优先级从低到高列出所有存在的配置文件
+fn existing_config_files(config_path: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = system_config_path().into_iter().collect();
+    files.push(config_path.to_path_buf());
+    if let Some(root) = find_repo_root() {
+        files.push(root.join(REPO_CONFIG_FILE));
+        files.push(root.join(REPO_LOCAL_CONFIG_FILE));
+    }
+    files.retain(|path| path.exists());
+    files
+}
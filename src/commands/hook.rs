@@ -1,17 +1,17 @@
-use crate::git::{
-    check_git_available, ensure_git_repo, get_pre_push_hook_path, has_pre_push_hook,
-    install_pre_push_hook, is_push_backup_hook_installed, uninstall_pre_push_hook,
-};
 use anyhow::Result;
 use std::io::{self, Write};
+use yewpb::git::{
+    get_pre_push_hook_path, has_pre_push_hook, install_pre_push_hook,
+    is_push_backup_hook_installed, uninstall_pre_push_hook, GitBackend,
+};
 
 /// 安装 pre-push hook
-pub fn execute_install(yes: bool) -> Result<()> {
-    check_git_available()?;
-    ensure_git_repo()?;
+pub fn execute_install(git: &dyn GitBackend, yes: bool) -> Result<()> {
+    git.check_available()?;
+    git.ensure_repo()?;
 
-    let already_installed = is_push_backup_hook_installed()?;
-    let has_hook = has_pre_push_hook()?;
+    let already_installed = is_push_backup_hook_installed(git)?;
+    let has_hook = has_pre_push_hook(git)?;
 
     if already_installed {
         println!("pre-push hook 已安装");
@@ -42,9 +42,9 @@ pub fn execute_install(yes: bool) -> Result<()> {
         }
     }
 
-    install_pre_push_hook()?;
+    install_pre_push_hook(git)?;
 
-    let hook_path = get_pre_push_hook_path()?;
+    let hook_path = get_pre_push_hook_path(git)?;
     println!("pre-push hook 已成功安装");
     println!("位置: {}", hook_path.display());
     println!();
@@ -55,11 +55,11 @@ pub fn execute_install(yes: bool) -> Result<()> {
 }
 
 /// 卸载 pre-push hook
-pub fn execute_uninstall(yes: bool) -> Result<()> {
-    check_git_available()?;
-    ensure_git_repo()?;
+pub fn execute_uninstall(git: &dyn GitBackend, yes: bool) -> Result<()> {
+    git.check_available()?;
+    git.ensure_repo()?;
 
-    if !is_push_backup_hook_installed()? {
+    if !is_push_backup_hook_installed(git)? {
         println!("pre-push hook 未安装");
         return Ok(());
     }
@@ -76,25 +76,25 @@ pub fn execute_uninstall(yes: bool) -> Result<()> {
         }
     }
 
-    uninstall_pre_push_hook()?;
+    uninstall_pre_push_hook(git)?;
     println!("pre-push hook 已成功卸载");
 
     Ok(())
 }
 
 /// 查看 hook 安装状态
-pub fn execute_status() -> Result<()> {
-    check_git_available()?;
-    ensure_git_repo()?;
+pub fn execute_status(git: &dyn GitBackend) -> Result<()> {
+    git.check_available()?;
+    git.ensure_repo()?;
 
-    let hook_path = get_pre_push_hook_path()?;
+    let hook_path = get_pre_push_hook_path(git)?;
 
-    if !has_pre_push_hook()? {
+    if !has_pre_push_hook(git)? {
         println!("状态: pre-push hook 未安装");
         println!("位置: {}", hook_path.display());
         println!();
         println!("运行 'yewpb hook install' 安装 hook");
-    } else if is_push_backup_hook_installed()? {
+    } else if is_push_backup_hook_installed(git)? {
         println!("状态: yewpb hook 已安装");
         println!("位置: {}", hook_path.display());
         println!();
@@ -109,3 +109,39 @@ pub fn execute_status() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use yewpb::git::FakeGit;
+
+    #[test]
+    fn install_keeps_existing_hook_and_uninstall_restores_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = dir.path().join("hooks");
+        fs::create_dir_all(&hooks).unwrap();
+        fs::write(hooks.join("pre-push"), "#!/bin/sh\necho existing\n").unwrap();
+        let git = FakeGit::new().with_git_dir(dir.path());
+
+        execute_install(&git, true).unwrap();
+        let installed = fs::read_to_string(hooks.join("pre-push")).unwrap();
+        assert!(installed.starts_with("#!/bin/sh\necho existing"));
+        assert!(is_push_backup_hook_installed(&git).unwrap());
+
+        execute_uninstall(&git, true).unwrap();
+        let restored = fs::read_to_string(hooks.join("pre-push")).unwrap();
+        assert_eq!(restored, "#!/bin/sh\necho existing\n");
+    }
+
+    #[test]
+    fn uninstall_removes_installed_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new().with_git_dir(dir.path());
+
+        execute_install(&git, true).unwrap();
+        execute_uninstall(&git, true).unwrap();
+
+        assert!(!is_push_backup_hook_installed(&git).unwrap());
+    }
+}
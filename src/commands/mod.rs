@@ -1,18 +1,23 @@
 mod alias;
-mod apply;
+pub mod apply;
+pub mod apply_recursive;
 mod check;
 mod clean;
 pub mod config;
 pub mod hook;
 mod markdown;
 mod push;
+mod reachability;
+mod restore;
 mod status;
 
 pub use alias::execute as alias;
 pub use apply::execute as apply;
+pub use apply_recursive::execute as apply_recursive;
 pub use check::execute as check;
 pub use clean::execute as clean;
 pub use config::execute as config;
 pub use markdown::execute as markdown;
 pub use push::execute as push;
+pub use restore::execute as restore;
 pub use status::execute as status;
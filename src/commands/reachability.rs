@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use std::time::Duration;
+use yewpb::git::{GitBackend, GitOverrides};
+
+/// 一个需要检查可用性的地址
+pub struct Probe {
+    /// 进度条中显示的名称
+    pub name: String,
+    pub url: String,
+    pub overrides: GitOverrides,
+    /// 超时时间（秒），批量模式下每个仓库可以不同
+    pub timeout: u64,
+}
+
+/// 未指定 --jobs 时同时检查的数量上限。检查主要等待网络，与 CPU 核数无关，
+/// 取一个较小的常数，避免仓库很多时同时启动大量 git 进程
+pub const DEFAULT_JOBS: usize = 8;
+
+/// 并发检查所有地址的可用性，返回值与 probes 的顺序一致
+///
+/// jobs 为同时检查的最大数量，None 表示使用 DEFAULT_JOBS。检查期间显示进度条，
+/// 结束后清除，由调用方按顺序输出结果
+pub fn check_all(
+    git: &dyn GitBackend,
+    probes: &[Probe],
+    jobs: Option<usize>,
+) -> Result<Vec<Result<bool>>> {
+    if probes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(DEFAULT_JOBS).min(probes.len()))
+        .build()
+        .context("创建检查线程池失败")?;
+
+    let mp = MultiProgress::new();
+    let style = ProgressStyle::default_spinner()
+        .template("{spinner:.cyan} {wide_msg}")
+        .unwrap();
+    let bars: Vec<ProgressBar> = probes
+        .iter()
+        .map(|probe| {
+            let pb = mp.add(ProgressBar::new_spinner());
+            pb.set_style(style.clone());
+            pb.set_message(format!("○ {} 等待中", probe.name));
+            pb
+        })
+        .collect();
+
+    let results = pool.install(|| {
+        probes
+            .par_iter()
+            .zip(&bars)
+            .map(|(probe, pb)| {
+                pb.enable_steady_tick(Duration::from_millis(100));
+                pb.set_message(format!("◐ {} 检查可用性...", probe.name));
+                let result = git.is_reachable(&probe.url, probe.timeout, &probe.overrides);
+                pb.finish_and_clear();
+                result
+            })
+            .collect()
+    });
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yewpb::git::fixtures::{A, B, C};
+    use yewpb::git::{FakeBehavior, FakeGit};
+
+    fn probe(url: &str) -> Probe {
+        Probe {
+            name: url.to_string(),
+            url: url.to_string(),
+            overrides: GitOverrides::default(),
+            timeout: 3,
+        }
+    }
+
+    #[test]
+    fn keeps_input_order_with_limited_jobs() {
+        let git = FakeGit::new()
+            .with_server(A, FakeBehavior::Ok)
+            .with_server(B, FakeBehavior::Timeout)
+            .with_server(C, FakeBehavior::AuthFailure);
+        let probes = [probe(A), probe(B), probe(C), probe(A)];
+
+        for jobs in [None, Some(1), Some(2)] {
+            let results = check_all(&git, &probes, jobs).unwrap();
+            assert_eq!(results.len(), 4);
+            assert!(results[0].as_ref().unwrap());
+            assert!(results[1]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("检查超时（3秒）"));
+            assert!(!results[2].as_ref().unwrap());
+            assert!(results[3].as_ref().unwrap());
+        }
+    }
+}
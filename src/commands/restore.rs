@@ -0,0 +1,108 @@
+use anyhow::Result;
+use yewpb::git::GitBackend;
+use yewpb::snapshot::{find_snapshot, list_snapshots, save_snapshot, SnapshotFile, MAX_SNAPSHOTS};
+
+pub fn execute(git: &dyn GitBackend, list: bool, id: Option<String>) -> Result<()> {
+    git.check_available()?;
+    git.ensure_repo()?;
+
+    if list {
+        return list_all(git);
+    }
+    let file = match id {
+        Some(id) => find_snapshot(git, &id)?,
+        None => match list_snapshots(git)?.into_iter().next() {
+            Some(file) => file,
+            None => {
+                println!("没有可恢复的远程仓库快照。");
+                return Ok(());
+            }
+        },
+    };
+    restore_snapshot(git, &file)
+}
+
+/// 列出所有快照，最新的在前
+fn list_all(git: &dyn GitBackend) -> Result<()> {
+    let snapshots = list_snapshots(git)?;
+    if snapshots.is_empty() {
+        println!("没有远程仓库快照。");
+        return Ok(());
+    }
+    println!("远程仓库快照（最多保留 {} 个，最新的在前）:", MAX_SNAPSHOTS);
+    for file in &snapshots {
+        let summary = match file.load() {
+            Ok(snapshot) if snapshot.remotes.is_empty() => {
+                format!("{:<8} 无远程仓库", snapshot.command)
+            }
+            Ok(snapshot) => format!(
+                "{:<8} {}",
+                snapshot.command,
+                snapshot.remote_names().join(", ")
+            ),
+            Err(_) => "无法解析".to_string(),
+        };
+        println!("  {:<20} {}  {}", file.id, file.time(), summary);
+    }
+    println!();
+    println!("使用 yewpb restore <编号> 恢复指定快照，不指定编号时恢复最近一次快照。");
+    Ok(())
+}
+
+/// 按快照重建远程仓库
+pub fn restore_snapshot(git: &dyn GitBackend, file: &SnapshotFile) -> Result<()> {
+    let snapshot = file.load()?;
+    // 恢复前的状态同样会保存为快照，可以再次 restore 撤销本次恢复
+    let saved = save_snapshot(git, "restore")?;
+    snapshot.restore(git)?;
+
+    let names = snapshot.remote_names();
+    println!(
+        "已恢复到 {} 的远程仓库（{}）: {}",
+        file.time(),
+        file.id,
+        if names.is_empty() {
+            "无远程仓库".to_string()
+        } else {
+            names.join(", ")
+        }
+    );
+    println!("恢复前的远程仓库已保存为快照 {}。", saved.id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yewpb::git::fixtures::A;
+    use yewpb::git::FakeGit;
+    use yewpb::snapshot::save_snapshot;
+
+    const ORIGIN: &str = "https://origin.example.com/demo.git";
+
+    #[test]
+    fn restores_latest_snapshot_and_can_be_undone() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote("origin", ORIGIN);
+        save_snapshot(&git, "apply").unwrap();
+        git.add_remote("yewpb", A).unwrap();
+
+        execute(&git, false, None).unwrap();
+        assert!(git.remote("yewpb").is_none());
+        assert_eq!(git.remote("origin").unwrap().0, ORIGIN);
+
+        execute(&git, false, None).unwrap();
+        assert!(git.remote("yewpb").is_some());
+    }
+
+    #[test]
+    fn unknown_snapshot_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new().with_git_dir(dir.path());
+
+        let err = execute(&git, false, Some("1999".to_string())).unwrap_err();
+        assert!(err.to_string().contains("未找到快照"));
+    }
+}
@@ -1,76 +1,67 @@
-use crate::config::{load_config, Config};
-use crate::git::{
-    check_git_available, current_branch, ensure_git_repo, git_count_ahead_behind,
-    git_ls_remote_ref, git_remote_names, run_git_get_push_urls,
-};
 use anyhow::Result;
 use std::path::Path;
+use yewpb::config::load_merged_config;
+use yewpb::git::{GitBackend, GitOverrides};
+use yewpb::utils::repo_name_from_url;
+use yewpb::{ManagedRemotes, REMOTE_NAME};
 
-const REMOTE_NAME: &str = "yewpb";
+pub fn execute(git: &dyn GitBackend, config_path: &Path) -> Result<()> {
+    git.check_available()?;
+    git.ensure_repo()?;
 
-pub fn execute(config_path: &Path) -> Result<()> {
-    check_git_available()?;
-    ensure_git_repo()?;
+    let config = load_merged_config(config_path)?;
 
-    // 检查 yewpb 远程是否存在
-    let remotes = git_remote_names()?;
-    if !remotes.contains(REMOTE_NAME) {
+    // 检查 yewpb 创建的远程仓库是否存在
+    let managed = ManagedRemotes::detect(git, &config)?;
+    if managed.layout().is_none() {
         println!("错误: 未找到 yewpb 远程仓库。");
         println!("提示: 请先运行 `yewpb apply` 应用配置。");
         return Ok(());
     }
 
-    let branch = current_branch()?;
-    let urls = run_git_get_push_urls(REMOTE_NAME)?;
+    let branch = git.current_branch()?;
+    let urls = managed.push_urls();
 
     if urls.is_empty() {
         println!("错误: 远程仓库 '{}' 未配置推送地址。", REMOTE_NAME);
         return Ok(());
     }
 
-    let config = load_config(config_path)?;
-
     println!("分支: {}\n", branch);
 
     for url in urls {
-        let name = match_config_name(&config, &url);
-        print_sync_status(&name, &url, &branch)?;
-    }
-
-    Ok(())
-}
-
-/// 匹配 URL 对应的配置名称
-fn match_config_name(config: &Config, url: &str) -> String {
-    // 按 base 长度降序排序，确保最长前缀匹配
-    let mut remotes = config.remotes.clone();
-    remotes.sort_by(|a, b| b.base.len().cmp(&a.base.len()));
-
-    for remote in &remotes {
-        if url.starts_with(&remote.base) {
-            let remainder = &url[remote.base.len()..];
-            if remainder.is_empty()
-                || remote.base.ends_with('/')
-                || remote.base.ends_with(':')
-                || remainder.starts_with('/')
-                || remainder.starts_with(':')
-            {
-                return remote.name.clone();
-            }
+        if let Some((remote, reason)) = config.match_disabled(&url) {
+            println!("{:12} - {}", format!("{}:", remote.name), reason);
+            continue;
         }
+        let name = config.display_name(&url);
+        let repo = repo_name_from_url(&url).unwrap_or_default();
+        let overrides = config
+            .match_remote(&url)
+            .map(|remote| remote.git_overrides(&config.defaults))
+            .unwrap_or_default();
+        print_sync_status(git, &name, &repo, &url, &branch, &overrides)?;
     }
 
-    "未命名".to_string()
+    Ok(())
 }
 
 /// 打印同步状态
-fn print_sync_status(name: &str, url: &str, branch: &str) -> Result<()> {
+fn print_sync_status(
+    git: &dyn GitBackend,
+    name: &str,
+    repo: &str,
+    url: &str,
+    branch: &str,
+    overrides: &GitOverrides,
+) -> Result<()> {
     // 获取远程分支的 commit hash
-    match git_ls_remote_ref(url, branch)? {
-        Some(remote_commit) => {
-            let (ahead, behind) = git_count_ahead_behind(&remote_commit)?;
+    match git.ls_remote_ref(url, branch, overrides) {
+        Err(e) => println!("{:12} {:20} ✗ {}", format!("{}:", name), repo, e),
+        Ok(Some(remote_commit)) => {
+            let (ahead, behind) = git.count_ahead_behind(&remote_commit)?;
             if ahead == 0 && behind == 0 {
-                println!("{:12} ✓ 已同步", format!("{}:", name));
+                println!("{:12} {:20} ✓ 已同步", format!("{}:", name), repo);
             } else {
                 let mut status_parts = Vec::new();
                 if ahead > 0 {
@@ -80,18 +71,20 @@ fn print_sync_status(name: &str, url: &str, branch: &str) -> Result<()> {
                     status_parts.push(format!("落后 {} 个提交", behind));
                 }
                 println!(
-                    "{:12} ↑{} ↓{} ({})",
+                    "{:12} {:20} ↑{} ↓{} ({})",
                     format!("{}:", name),
+                    repo,
                     ahead,
                     behind,
                     status_parts.join(", ")
                 );
             }
         }
-        None => {
+        Ok(None) => {
             println!(
-                "{:12} ✗ 无法获取远程状态 (分支不存在或连接失败)",
-                format!("{}:", name)
+                "{:12} {:20} ✗ 无法获取远程状态 (分支不存在或连接失败)",
+                format!("{}:", name),
+                repo
             );
         }
     }
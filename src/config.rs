@@ -1,87 +0,0 @@
-use anyhow::{Context, Result};
-use directories::ProjectDirs;
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::{Path, PathBuf};
-
-// 硬编码默认值常量
-pub const DEFAULT_RETRY: u32 = 3;
-pub const DEFAULT_RETRY_DELAY: u64 = 1000;
-pub const DEFAULT_PUSH_TIMEOUT: u64 = 30;
-pub const DEFAULT_CHECK_TIMEOUT: u64 = 10;
-pub const DEFAULT_ALIAS: &str = "pb";
-
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct Config {
-    pub remotes: Vec<Remote>,
-    #[serde(default)]
-    pub defaults: Defaults,
-}
-
-/// 默认配置项
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct Defaults {
-    /// 最大重试次数（push 命令）
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub retry: Option<u32>,
-    /// 重试间隔毫秒数（push 命令）
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub retry_delay: Option<u64>,
-    /// push 命令超时时间（秒）
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub timeout: Option<u64>,
-    /// apply/check 命令超时时间（秒）
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub check_timeout: Option<u64>,
-    /// git alias 默认名称
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub alias: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Remote {
-    pub name: String,
-    #[serde(alias = "url")]
-    pub base: String,
-    pub note: Option<String>,
-}
-
-pub fn config_path() -> Result<PathBuf> {
-    // 计算配置文件路径
-    // 开发环境下将配置存到项目根目录的 .dev 文件夹
-    if std::env::var("YEWPB_ENV")
-        .map(|value| value.eq_ignore_ascii_case("dev"))
-        .unwrap_or(false)
-    {
-        let current_dir = std::env::current_dir().context("获取当前目录失败")?;
-        return Ok(current_dir.join(".dev").join("config.toml"));
-    }
-
-    // 使用系统推荐的配置目录，避免污染项目仓库
-    let project_dirs =
-        ProjectDirs::from("com", "yewfence", "yewpb").context("获取配置目录失败")?;
-    Ok(project_dirs.config_dir().join("config.toml"))
-}
-
-pub fn load_config(path: &Path) -> Result<Config> {
-    // 读取本地配置，不存在时返回空配置
-    if !path.exists() {
-        return Ok(Config::default());
-    }
-    let content = fs::read_to_string(path)
-        .with_context(|| format!("读取配置文件失败: {}", path.display()))?;
-    let config = toml::from_str(&content)
-        .with_context(|| format!("配置文件格式不合法: {}", path.display()))?;
-    Ok(config)
-}
-
-pub fn save_config(path: &Path, config: &Config) -> Result<()> {
-    // 保存配置到本地文件
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .with_context(|| format!("创建配置目录失败: {}", parent.display()))?;
-    }
-    let content = toml::to_string_pretty(config).context("序列化配置失败")?;
-    fs::write(path, content).with_context(|| format!("写入配置文件失败: {}", path.display()))?;
-    Ok(())
-}
@@ -0,0 +1,151 @@
+use super::Config;
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDateTime};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 最多保留的备份数量，超出后删除最旧的备份
+pub const MAX_BACKUPS: usize = 20;
+
+/// 备份文件名前缀
+const BACKUP_PREFIX: &str = "config-";
+/// 备份编号中的时间格式
+const ID_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// 一个配置备份
+pub struct Backup {
+    /// 备份编号，即创建时间，同一秒内的多个备份追加序号
+    pub id: String,
+    pub path: PathBuf,
+}
+
+impl Backup {
+    /// 备份的创建时间，用于展示
+    pub fn time(&self) -> String {
+        let stamp = self.id.get(..15).unwrap_or(&self.id);
+        match NaiveDateTime::parse_from_str(stamp, ID_FORMAT) {
+            Ok(time) => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            Err(_) => self.id.clone(),
+        }
+    }
+
+    /// 备份中的远程仓库数量，无法解析时返回 None
+    pub fn remote_count(&self) -> Option<usize> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        let config: Config = toml::from_str(&content).ok()?;
+        Some(config.remotes.len())
+    }
+}
+
+/// 备份目录，位于配置文件同级的 backups 目录
+pub fn backup_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+        .join("backups")
+}
+
+/// 按时间从新到旧列出所有备份
+pub fn list_backups(config_path: &Path) -> Result<Vec<Backup>> {
+    let dir = backup_dir(config_path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<Backup> = fs::read_dir(&dir)
+        .with_context(|| format!("读取备份目录失败: {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let id = name.strip_prefix(BACKUP_PREFIX)?.strip_suffix(".toml")?;
+            Some(Backup {
+                id: id.to_string(),
+                path: entry.path(),
+            })
+        })
+        .collect();
+    // 编号以时间开头，同一秒内的序号递增，按 (时间, 序号) 排序
+    backups.sort_by_key(|b| std::cmp::Reverse(sort_key(&b.id)));
+    Ok(backups)
+}
+
+fn sort_key(id: &str) -> (String, u32) {
+    match id.get(15..).and_then(|s| s.strip_prefix('-')) {
+        Some(seq) => (id[..15].to_string(), seq.parse().unwrap_or(0)),
+        None => (id.to_string(), 0),
+    }
+}
+
+/// 按编号查找备份，支持唯一的编号前缀
+pub fn find_backup(config_path: &Path, id: &str) -> Result<Backup> {
+    let mut matches: Vec<Backup> = list_backups(config_path)?
+        .into_iter()
+        .filter(|b| b.id.starts_with(id))
+        .collect();
+    match matches.len() {
+        0 => bail!(
+            "未找到备份 '{}'，运行 yewpb config history 查看所有备份",
+            id
+        ),
+        1 => Ok(matches.remove(0)),
+        _ => match matches.iter().position(|b| b.id == id) {
+            Some(i) => Ok(matches.remove(i)),
+            None => bail!(
+                "备份编号 '{}' 不唯一，匹配到: {}",
+                id,
+                matches
+                    .iter()
+                    .map(|b| b.id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        },
+    }
+}
+
+/// 将内容保存为一个新的备份，并清理超出数量的旧备份
+pub fn create_backup(config_path: &Path, content: &str) -> Result<Backup> {
+    let dir = backup_dir(config_path);
+    fs::create_dir_all(&dir).with_context(|| format!("创建备份目录失败: {}", dir.display()))?;
+
+    let stamp = Local::now().format(ID_FORMAT).to_string();
+    let mut id = stamp.clone();
+    let mut seq = 1;
+    while dir.join(backup_file_name(&id)).exists() {
+        id = format!("{}-{}", stamp, seq);
+        seq += 1;
+    }
+    let path = dir.join(backup_file_name(&id));
+    write_atomic(&path, content)?;
+
+    for old in list_backups(config_path)?.into_iter().skip(MAX_BACKUPS) {
+        let _ = fs::remove_file(&old.path);
+    }
+    Ok(Backup { id, path })
+}
+
+fn backup_file_name(id: &str) -> String {
+    format!("{}{}.toml", BACKUP_PREFIX, id)
+}
+
+/// 先写入同目录下的临时文件再重命名，避免写入中断导致配置文件损坏
+pub fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    let result = (|| -> Result<()> {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result.with_context(|| format!("写入配置文件失败: {}", path.display()))
+}
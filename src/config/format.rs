@@ -0,0 +1,66 @@
+use super::Config;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// 配置导入导出支持的文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// 根据文件扩展名推断格式，无法识别时返回 None
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "toml" => Some(ConfigFormat::Toml),
+            "json" => Some(ConfigFormat::Json),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    /// 根据内容粗略推断格式：JSON 以 { 开头，其余按 TOML 处理
+    pub fn detect(content: &str) -> Self {
+        if content.trim_start().starts_with('{') {
+            ConfigFormat::Json
+        } else {
+            ConfigFormat::Toml
+        }
+    }
+
+    /// 该格式的默认扩展名
+    pub fn extension(self) -> &'static str {
+        match self {
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Json => "json",
+            ConfigFormat::Yaml => "yaml",
+        }
+    }
+
+    /// 序列化配置
+    pub fn serialize(self, config: &Config) -> Result<String> {
+        let content = match self {
+            ConfigFormat::Toml => toml::to_string_pretty(config).context("序列化 TOML 失败")?,
+            ConfigFormat::Json => {
+                let mut json = serde_json::to_string_pretty(config).context("序列化 JSON 失败")?;
+                json.push('\n');
+                json
+            }
+            ConfigFormat::Yaml => serde_yaml::to_string(config).context("序列化 YAML 失败")?,
+        };
+        Ok(content)
+    }
+
+    /// 解析配置
+    pub fn parse(self, content: &str) -> Result<Config> {
+        let config = match self {
+            ConfigFormat::Toml => toml::from_str(content).context("TOML 格式不合法")?,
+            ConfigFormat::Json => serde_json::from_str(content).context("JSON 格式不合法")?,
+            ConfigFormat::Yaml => serde_yaml::from_str(content).context("YAML 格式不合法")?,
+        };
+        Ok(config)
+    }
+}
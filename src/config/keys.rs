@@ -0,0 +1,236 @@
+use super::Defaults;
+use crate::proxy;
+use anyhow::{bail, Context, Result};
+use std::fmt;
+
+/// 配置项的取值类型
+pub enum KeyKind {
+    /// 非负整数，取值范围为闭区间 [min, max]
+    Number { min: u64, max: u64 },
+    /// git alias 名称，只能包含字母、数字和 -
+    AliasName,
+    /// 分组名称，不能为空或包含空白字符
+    GroupName,
+    /// 远程仓库名称，不能为空或包含空白字符
+    RemoteName,
+    /// 代理地址，如 http://host:port、socks5://host:port
+    ProxyUrl,
+    /// 逗号分隔的主机列表
+    HostList,
+}
+
+impl fmt::Display for KeyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyKind::Number { min, max } => write!(f, "整数 {}-{}", min, max),
+            KeyKind::AliasName => write!(f, "alias 名称"),
+            KeyKind::GroupName => write!(f, "分组名称"),
+            KeyKind::RemoteName => write!(f, "远程仓库名称"),
+            KeyKind::ProxyUrl => write!(f, "代理地址"),
+            KeyKind::HostList => write!(f, "逗号分隔的主机列表"),
+        }
+    }
+}
+
+/// [defaults] 中的一个配置项
+pub struct DefaultKey {
+    /// 配置项名称，与 TOML 中的键一致
+    pub name: &'static str,
+    /// 取值类型
+    pub kind: KeyKind,
+    /// 配置项说明
+    pub description: &'static str,
+}
+
+impl DefaultKey {
+    /// 对应的环境变量名，如 retry_delay -> YEWPB_RETRY_DELAY
+    pub fn env_var(&self) -> String {
+        format!("YEWPB_{}", self.name.to_uppercase())
+    }
+
+    /// 按类型校验取值，返回去除首尾空白后的值
+    pub(super) fn validate<'a>(&self, value: &'a str) -> Result<&'a str> {
+        let value = value.trim();
+        match self.kind {
+            KeyKind::Number { min, max } => {
+                let number: u64 = match value.parse() {
+                    Ok(number) => number,
+                    Err(_) => bail!("配置项 '{}' 需要非负整数，实际为 '{}'", self.name, value),
+                };
+                if number < min || number > max {
+                    bail!(
+                        "配置项 '{}' 的取值范围为 {}-{}，实际为 {}",
+                        self.name,
+                        min,
+                        max,
+                        number
+                    );
+                }
+            }
+            KeyKind::AliasName => {
+                if value.is_empty()
+                    || value.starts_with('-')
+                    || !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                {
+                    bail!(
+                        "配置项 '{}' 只能包含字母、数字和 -，且不能以 - 开头，实际为 '{}'",
+                        self.name,
+                        value
+                    );
+                }
+            }
+            KeyKind::ProxyUrl => {
+                proxy::validate(value).with_context(|| format!("配置项 '{}' 无效", self.name))?;
+            }
+            KeyKind::HostList => {
+                if value.split(',').any(|host| {
+                    let host = host.trim();
+                    host.is_empty() || host.contains(char::is_whitespace)
+                }) {
+                    bail!(
+                        "配置项 '{}' 应为逗号分隔的主机列表，实际为 '{}'",
+                        self.name,
+                        value
+                    );
+                }
+            }
+            KeyKind::GroupName | KeyKind::RemoteName => {
+                if value.is_empty() || value.chars().any(char::is_whitespace) {
+                    bail!(
+                        "配置项 '{}' 不能为空或包含空白字符，实际为 '{}'",
+                        self.name,
+                        value
+                    );
+                }
+            }
+        }
+        Ok(value)
+    }
+}
+
+/// 所有已知的 [defaults] 配置项
+pub const DEFAULT_KEYS: &[DefaultKey] = &[
+    DefaultKey {
+        name: "retry",
+        kind: KeyKind::Number { min: 0, max: 20 },
+        description: "推送失败时的最大重试次数（push 命令）",
+    },
+    DefaultKey {
+        name: "retry_delay",
+        kind: KeyKind::Number {
+            min: 0,
+            max: 600_000,
+        },
+        description: "重试间隔毫秒数（push 命令）",
+    },
+    DefaultKey {
+        name: "timeout",
+        kind: KeyKind::Number {
+            min: 0,
+            max: 86_400,
+        },
+        description: "推送超时时间（秒），0 表示不限制（push 命令）",
+    },
+    DefaultKey {
+        name: "check_timeout",
+        kind: KeyKind::Number { min: 1, max: 600 },
+        description: "连接检查超时时间（秒）（apply/check 命令）",
+    },
+    DefaultKey {
+        name: "alias",
+        kind: KeyKind::AliasName,
+        description: "git alias 默认名称（alias 命令）",
+    },
+    DefaultKey {
+        name: "group",
+        kind: KeyKind::GroupName,
+        description: "默认使用的远程仓库分组（apply/push 命令）",
+    },
+    DefaultKey {
+        name: "fetch_remote",
+        kind: KeyKind::RemoteName,
+        description: "作为拉取地址的远程仓库，优先于 primary 标记（apply 命令）",
+    },
+    DefaultKey {
+        name: "proxy",
+        kind: KeyKind::ProxyUrl,
+        description: "访问远程仓库时使用的代理（push/check/status 命令）",
+    },
+    DefaultKey {
+        name: "no_proxy",
+        kind: KeyKind::HostList,
+        description: "不使用代理的主机，逗号分隔，.example.com 匹配子域名",
+    },
+];
+
+/// 按名称查找配置项，允许使用 - 代替 _
+pub fn find_key(name: &str) -> Result<&'static DefaultKey> {
+    let normalized = name.trim().replace('-', "_");
+    match DEFAULT_KEYS.iter().find(|key| key.name == normalized) {
+        Some(key) => Ok(key),
+        None => bail!(
+            "未知的配置项 '{}'，可用配置项: {}（运行 yewpb config defaults keys 查看说明）",
+            name,
+            DEFAULT_KEYS
+                .iter()
+                .map(|key| key.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+impl Defaults {
+    /// 以字符串形式读取配置项，未设置时返回 None
+    pub fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "retry" => self.retry.map(|v| v.to_string()),
+            "retry_delay" => self.retry_delay.map(|v| v.to_string()),
+            "timeout" => self.timeout.map(|v| v.to_string()),
+            "check_timeout" => self.check_timeout.map(|v| v.to_string()),
+            "alias" => self.alias.clone(),
+            "group" => self.group.clone(),
+            "fetch_remote" => self.fetch_remote.clone(),
+            "proxy" => self.proxy.clone(),
+            "no_proxy" => self.no_proxy.clone(),
+            _ => None,
+        }
+    }
+
+    /// 校验并设置配置项
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let key = find_key(key)?;
+        let value = key.validate(value)?;
+        // 数值已经过范围校验，转换不会失败
+        match key.name {
+            "retry" => self.retry = value.parse().ok(),
+            "retry_delay" => self.retry_delay = value.parse().ok(),
+            "timeout" => self.timeout = value.parse().ok(),
+            "check_timeout" => self.check_timeout = value.parse().ok(),
+            "alias" => self.alias = Some(value.to_string()),
+            "group" => self.group = Some(value.to_string()),
+            "fetch_remote" => self.fetch_remote = Some(value.to_string()),
+            "proxy" => self.proxy = Some(value.to_string()),
+            "no_proxy" => self.no_proxy = Some(value.to_string()),
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// 清除配置项，返回之前是否已设置
+    pub fn unset(&mut self, key: &str) -> Result<bool> {
+        let was_set = match find_key(key)?.name {
+            "retry" => self.retry.take().is_some(),
+            "retry_delay" => self.retry_delay.take().is_some(),
+            "timeout" => self.timeout.take().is_some(),
+            "check_timeout" => self.check_timeout.take().is_some(),
+            "alias" => self.alias.take().is_some(),
+            "group" => self.group.take().is_some(),
+            "fetch_remote" => self.fetch_remote.take().is_some(),
+            "proxy" => self.proxy.take().is_some(),
+            "no_proxy" => self.no_proxy.take().is_some(),
+            _ => unreachable!(),
+        };
+        Ok(was_set)
+    }
+}
@@ -0,0 +1,272 @@
+use super::keys::DEFAULT_KEYS;
+use super::{
+    find_repo_root, load_config, load_repo_config, Config, Defaults, DEFAULT_ALIAS,
+    DEFAULT_CHECK_TIMEOUT, DEFAULT_PUSH_TIMEOUT, DEFAULT_RETRY, DEFAULT_RETRY_DELAY,
+    REPO_CONFIG_FILE, REPO_LOCAL_CONFIG_FILE,
+};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// 配置值的来源层，按优先级从低到高排列
+#[derive(Debug, Clone)]
+pub enum Origin {
+    /// 内置默认值
+    Builtin,
+    /// 系统配置文件
+    System(PathBuf),
+    /// 用户配置文件
+    User(PathBuf),
+    /// 仓库根目录下的项目配置文件
+    Repo(PathBuf),
+    /// YEWPB_* 环境变量
+    Env,
+    /// 命令行参数
+    Cli,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Builtin => write!(f, "内置默认值"),
+            Origin::System(path) => write!(f, "系统配置 ({})", path.display()),
+            Origin::User(path) => write!(f, "用户配置 ({})", path.display()),
+            Origin::Repo(path) => write!(f, "项目配置 ({})", path.display()),
+            Origin::Env => write!(f, "环境变量"),
+            Origin::Cli => write!(f, "命令行参数"),
+        }
+    }
+}
+
+/// 一个默认配置层及其来源
+pub struct Layer {
+    pub origin: Origin,
+    pub defaults: Defaults,
+}
+
+/// 按层叠加后的配置
+pub struct LayeredConfig {
+    /// 合并后的配置，defaults 中包含除内置默认值和命令行参数以外的所有层
+    pub config: Config,
+    /// 从低到高排列的默认配置层，第一层总是内置默认值
+    pub layers: Vec<Layer>,
+    /// 每个远程仓库最终生效的来源
+    pub remote_origins: BTreeMap<String, Origin>,
+}
+
+/// 叠加所有层后最终生效的默认值
+#[derive(Debug, Clone)]
+pub struct ResolvedDefaults {
+    pub retry: u32,
+    pub retry_delay: u64,
+    pub timeout: u64,
+    pub check_timeout: u64,
+    pub alias: String,
+    pub group: Option<String>,
+}
+
+impl Defaults {
+    /// 内置默认值，作为最底层
+    pub fn builtin() -> Self {
+        Defaults {
+            retry: Some(DEFAULT_RETRY),
+            retry_delay: Some(DEFAULT_RETRY_DELAY),
+            timeout: Some(DEFAULT_PUSH_TIMEOUT),
+            check_timeout: Some(DEFAULT_CHECK_TIMEOUT),
+            alias: Some(DEFAULT_ALIAS.to_string()),
+            group: None,
+            fetch_remote: None,
+            proxy: None,
+            no_proxy: None,
+        }
+    }
+
+    /// 从 YEWPB_* 环境变量读取默认配置，空值视为未设置
+    pub fn from_env() -> Result<Self> {
+        let mut defaults = Defaults::default();
+        for key in DEFAULT_KEYS {
+            let var = key.env_var();
+            if let Ok(value) = std::env::var(&var) {
+                if !value.trim().is_empty() {
+                    defaults
+                        .set(key.name, &value)
+                        .with_context(|| format!("环境变量 {} 的值无效", var))?;
+                }
+            }
+        }
+        Ok(defaults)
+    }
+}
+
+impl LayeredConfig {
+    /// 叠加命令行参数，得到最终生效的默认值
+    pub fn resolve(&self, cli: &Defaults) -> ResolvedDefaults {
+        let mut merged = Defaults::default();
+        for layer in &self.layers {
+            merged.merge(layer.defaults.clone());
+        }
+        merged.merge(cli.clone());
+
+        // 内置默认层保证以下配置项都有值
+        ResolvedDefaults {
+            retry: merged.retry.unwrap_or(DEFAULT_RETRY),
+            retry_delay: merged.retry_delay.unwrap_or(DEFAULT_RETRY_DELAY),
+            timeout: merged.timeout.unwrap_or(DEFAULT_PUSH_TIMEOUT),
+            check_timeout: merged.check_timeout.unwrap_or(DEFAULT_CHECK_TIMEOUT),
+            alias: merged.alias.unwrap_or_else(|| DEFAULT_ALIAS.to_string()),
+            group: merged.group,
+        }
+    }
+
+    /// 查找配置项最终生效的值及其来源，所有层都未设置时返回 None
+    pub fn lookup(&self, key: &str, cli: &Defaults) -> Option<(String, Origin)> {
+        if let Some(value) = cli.get(key) {
+            return Some((value, Origin::Cli));
+        }
+        self.layers.iter().rev().find_map(|layer| {
+            layer
+                .defaults
+                .get(key)
+                .map(|value| (value, layer.origin.clone()))
+        })
+    }
+}
+
+/// 系统级配置文件路径，可通过 YEWPB_SYSTEM_CONFIG 覆盖
+pub fn system_config_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("YEWPB_SYSTEM_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    if cfg!(windows) {
+        std::env::var("PROGRAMDATA")
+            .ok()
+            .map(|dir| PathBuf::from(dir).join("yewpb").join("config.toml"))
+    } else {
+        Some(PathBuf::from("/etc/yewpb/config.toml"))
+    }
+}
+
+/// 用户配置以外、取决于运行环境的配置来源
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSources {
+    /// 系统配置文件，None 表示不读取
+    pub system: Option<PathBuf>,
+    /// 读取项目配置的仓库根目录，None 表示不读取
+    pub repo_root: Option<PathBuf>,
+    /// 是否读取 YEWPB_* 环境变量
+    pub env: bool,
+}
+
+impl ConfigSources {
+    /// 当前进程的运行环境：系统配置、当前目录所在仓库的项目配置与环境变量
+    pub fn current() -> Self {
+        ConfigSources {
+            system: system_config_path(),
+            repo_root: find_repo_root(),
+            env: true,
+        }
+    }
+
+    /// 只读取指定仓库的项目配置，不受系统配置和环境变量影响，用于测试
+    pub fn isolated(repo_root: &Path) -> Self {
+        ConfigSources {
+            repo_root: Some(repo_root.to_path_buf()),
+            ..Default::default()
+        }
+    }
+}
+
+/// 依次叠加内置默认值、系统配置、用户配置、项目配置和环境变量
+pub fn load_layered_config(user_path: &Path) -> Result<LayeredConfig> {
+    load_layered_config_from(user_path, &ConfigSources::current())
+}
+
+/// 与 [`load_layered_config`] 相同，但项目配置从指定的仓库根目录读取，None 表示不读取项目配置
+pub fn load_layered_config_in(user_path: &Path, repo_root: Option<&Path>) -> Result<LayeredConfig> {
+    let sources = ConfigSources {
+        system: system_config_path(),
+        repo_root: repo_root.map(Path::to_path_buf),
+        env: true,
+    };
+    load_layered_config_from(user_path, &sources)
+}
+
+/// 按 sources 指定的来源叠加配置
+pub fn load_layered_config_from(
+    user_path: &Path,
+    sources: &ConfigSources,
+) -> Result<LayeredConfig> {
+    let mut layered = LayeredConfig {
+        config: Config::default(),
+        layers: vec![Layer {
+            origin: Origin::Builtin,
+            defaults: Defaults::builtin(),
+        }],
+        remote_origins: BTreeMap::new(),
+    };
+
+    // 系统配置与用户配置格式相同
+    let file_layers = sources
+        .system
+        .clone()
+        .map(|path| (path.clone(), Origin::System(path)))
+        .into_iter()
+        .chain([(
+            user_path.to_path_buf(),
+            Origin::User(user_path.to_path_buf()),
+        )]);
+    for (path, origin) in file_layers {
+        if !path.exists() {
+            continue;
+        }
+        let file_config = load_config(&path)?;
+        for remote in &file_config.remotes {
+            layered
+                .remote_origins
+                .insert(remote.name.clone(), origin.clone());
+        }
+        layered.layers.push(Layer {
+            origin,
+            defaults: file_config.defaults.clone(),
+        });
+        layered.config.merge_config(file_config);
+    }
+
+    // 项目配置，.yewpb.local.toml 优先级高于 .yewpb.toml
+    if let Some(root) = &sources.repo_root {
+        for file in [REPO_CONFIG_FILE, REPO_LOCAL_CONFIG_FILE] {
+            let path = root.join(file);
+            if let Some(repo_config) = load_repo_config(&path)? {
+                let origin = Origin::Repo(path);
+                for remote in &repo_config.remotes {
+                    layered
+                        .remote_origins
+                        .insert(remote.name.clone(), origin.clone());
+                }
+                layered.layers.push(Layer {
+                    origin,
+                    defaults: repo_config.defaults.clone(),
+                });
+                layered.config.merge_repo(repo_config);
+            }
+        }
+    }
+
+    // 所有层叠加完成后再剔除禁用的远程仓库，项目配置可以重新启用
+    layered.config.split_disabled();
+
+    // 环境变量
+    let env_defaults = if sources.env {
+        Defaults::from_env()?
+    } else {
+        Defaults::default()
+    };
+    layered.config.defaults.merge(env_defaults.clone());
+    layered.layers.push(Layer {
+        origin: Origin::Env,
+        defaults: env_defaults,
+    });
+
+    Ok(layered)
+}
@@ -0,0 +1,730 @@
+mod backup;
+mod format;
+mod keys;
+mod layers;
+mod validate;
+
+pub(crate) use backup::write_atomic;
+pub use backup::{create_backup, find_backup, list_backups, MAX_BACKUPS};
+pub use format::ConfigFormat;
+pub use keys::{find_key, DEFAULT_KEYS};
+pub use layers::{
+    load_layered_config, load_layered_config_from, load_layered_config_in, system_config_path,
+    ConfigSources, Origin, ResolvedDefaults,
+};
+pub use validate::{validate_file, validate_layered, validate_str, FileKind, Report};
+
+use crate::git::GitOverrides;
+use crate::proxy::{self, ProxySetting, Transport};
+use crate::rename::RenameRule;
+use crate::template::{self, TemplateContext};
+use crate::utils::{build_remote_url, expand_home};
+use anyhow::{bail, Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 硬编码默认值常量
+pub const DEFAULT_RETRY: u32 = 3;
+pub const DEFAULT_RETRY_DELAY: u64 = 1000;
+pub const DEFAULT_PUSH_TIMEOUT: u64 = 30;
+pub const DEFAULT_CHECK_TIMEOUT: u64 = 10;
+pub const DEFAULT_ALIAS: &str = "pb";
+
+/// 项目级配置文件名，放在仓库根目录，可提交到仓库
+pub const REPO_CONFIG_FILE: &str = ".yewpb.toml";
+/// 项目级本地配置文件名，放在仓库根目录，建议加入 .gitignore
+pub const REPO_LOCAL_CONFIG_FILE: &str = ".yewpb.local.toml";
+
+/// 无法匹配到配置时使用的显示名称
+pub const UNNAMED_REMOTE: &str = "未命名";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Config {
+    pub remotes: Vec<Remote>,
+    #[serde(default)]
+    pub defaults: Defaults,
+    /// 远程仓库分组，键为分组名，值为远程仓库名称列表
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub groups: BTreeMap<String, Vec<String>>,
+    /// 被禁用或被项目级配置禁用的远程仓库，仅存在于合并后的配置中
+    #[serde(skip)]
+    pub disabled: Vec<DisabledRemote>,
+}
+
+/// 合并配置时被移出的远程仓库
+#[derive(Debug, Clone)]
+pub struct DisabledRemote {
+    pub remote: Remote,
+    pub reason: DisableReason,
+}
+
+/// 远程仓库被跳过的原因，用于 apply/push/check/status 的提示
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisableReason {
+    /// 配置中设置了 enabled = false
+    Disabled,
+    /// 项目配置的 disable 列表中包含该仓库
+    RepoDisabled,
+}
+
+impl fmt::Display for DisableReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisableReason::Disabled => write!(f, "已禁用"),
+            DisableReason::RepoDisabled => write!(f, "已在项目配置中禁用"),
+        }
+    }
+}
+
+/// 项目级配置，叠加在全局配置之上
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RepoConfig {
+    /// 本项目额外添加的远程仓库，与全局配置同名时整体覆盖
+    #[serde(default)]
+    pub remotes: Vec<Remote>,
+    /// 在本项目中禁用的全局远程仓库名称
+    #[serde(default)]
+    pub disable: Vec<String>,
+    /// 覆盖全局的默认配置项
+    #[serde(default)]
+    pub defaults: Defaults,
+    /// 本项目定义的分组，与全局配置同名时覆盖
+    #[serde(default)]
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+/// 默认配置项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Defaults {
+    /// 最大重试次数（push 命令）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<u32>,
+    /// 重试间隔毫秒数（push 命令）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_delay: Option<u64>,
+    /// push 命令超时时间（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// apply/check 命令超时时间（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_timeout: Option<u64>,
+    /// git alias 默认名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// 默认使用的远程仓库分组（apply/push 命令）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// 作为 yewpb remote 拉取地址的远程仓库名称，优先于 primary 标记
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetch_remote: Option<String>,
+    /// 访问远程仓库时使用的代理
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// 不使用代理的主机，逗号分隔
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Remote {
+    pub name: String,
+    /// 是否启用，禁用的仓库保留配置但不参与 apply/push/check
+    #[serde(default = "default_enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+    /// 是否作为 yewpb remote 的拉取地址，未设置时使用第一个远程仓库
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub primary: bool,
+    /// 基础地址，生成时拼接为 `{base}/{repo}.git`
+    #[serde(alias = "url", default, skip_serializing_if = "String::is_empty")]
+    pub base: String,
+    /// URL 模板，设置后优先于 base，支持 {repo}、{owner}、{group}、{dir}、{branch_prefix}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// 模板变量，为 {owner}、{group}、{branch_prefix} 提供取值
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, String>,
+    /// 仓库名称映射规则，用于该平台上仓库名与本地不一致的情况
+    #[serde(default, skip_serializing_if = "RenameRule::is_empty")]
+    pub rename: RenameRule,
+    /// 推送超时时间（秒），覆盖 defaults.timeout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// 推送失败时的最大重试次数，覆盖 defaults.retry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<u32>,
+    /// 重试间隔毫秒数，覆盖 defaults.retry_delay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_delay: Option<u64>,
+    /// 是否允许强制推送，设为 false 表示受保护的仓库
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_force: Option<bool>,
+    /// 推送到该仓库时追加的 git push 参数
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
+    /// 推送到该仓库时是否跳过连接验证
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_check: Option<bool>,
+    /// 访问该仓库时使用的 SSH 私钥路径，支持 ~ 开头
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_key: Option<String>,
+    /// 访问该仓库时使用的 ssh 命令，相当于 GIT_SSH_COMMAND
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_command: Option<String>,
+    /// 访问该仓库时通过 git -c 传入的配置项
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub git_config: BTreeMap<String, String>,
+    /// 访问该仓库时使用的代理，覆盖 defaults.proxy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// 访问该仓库时不使用任何代理
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_proxy: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+impl Default for Remote {
+    fn default() -> Self {
+        Remote {
+            name: String::new(),
+            enabled: true,
+            primary: false,
+            base: String::new(),
+            template: None,
+            vars: BTreeMap::new(),
+            rename: RenameRule::default(),
+            timeout: None,
+            retry: None,
+            retry_delay: None,
+            allow_force: None,
+            extra_args: Vec::new(),
+            skip_check: None,
+            ssh_key: None,
+            ssh_command: None,
+            git_config: BTreeMap::new(),
+            proxy: None,
+            no_proxy: false,
+            note: None,
+        }
+    }
+}
+
+impl Remote {
+    /// 配置中用于展示的地址：优先显示模板
+    pub fn target(&self) -> &str {
+        self.template.as_deref().unwrap_or(&self.base)
+    }
+
+    /// 应用映射规则，得到该远程仓库上的最终仓库名称
+    pub fn repo_name(&self, repo: &str) -> Result<String> {
+        self.rename
+            .apply(repo)
+            .with_context(|| format!("远程仓库 '{}' 的仓库名称映射规则无效", self.name))
+    }
+
+    /// 生成该远程仓库在指定仓库名下的完整地址
+    pub fn build_url(&self, repo: &str, dir: &str) -> Result<String> {
+        let repo = self.repo_name(repo)?;
+        match &self.template {
+            Some(template) => {
+                let ctx = TemplateContext {
+                    repo: &repo,
+                    dir,
+                    vars: &self.vars,
+                };
+                template::render(template, &ctx)
+                    .with_context(|| format!("远程仓库 '{}' 的 URL 模板无效", self.name))
+            }
+            None if self.base.is_empty() => {
+                bail!("远程仓库 '{}' 未配置 base 或 template", self.name)
+            }
+            None => Ok(build_remote_url(&self.base, &repo)),
+        }
+    }
+
+    /// 访问该仓库时使用的代理：remote.no_proxy > remote.proxy > defaults.no_proxy > defaults.proxy
+    pub fn proxy(&self, defaults: &Defaults) -> ProxySetting {
+        if self.no_proxy {
+            return ProxySetting::Bypass;
+        }
+        if let Some(proxy) = &self.proxy {
+            return ProxySetting::Use(proxy.clone());
+        }
+        let bypassed = defaults.no_proxy.as_deref().is_some_and(|no_proxy| {
+            proxy::host_of(self.target())
+                .is_some_and(|host| proxy::matches_no_proxy(host, no_proxy))
+        });
+        match &defaults.proxy {
+            _ if bypassed => ProxySetting::Bypass,
+            Some(proxy) => ProxySetting::Use(proxy.clone()),
+            None => ProxySetting::Inherit,
+        }
+    }
+
+    /// 访问该仓库时附加的 git 调用参数，只作用于单次调用
+    ///
+    /// ssh 私钥与代理以参数形式追加到 ssh_command（未设置时为 ssh）之后；
+    /// http(s) 代理通过 http.proxy 传入，git_config 中的同名配置优先
+    pub fn git_overrides(&self, defaults: &Defaults) -> GitOverrides {
+        let quote =
+            |arg: &str| shlex::try_quote(arg).map_or_else(|_| arg.to_string(), |q| q.into_owned());
+        let mut ssh_args = Vec::new();
+        if let Some(key) = &self.ssh_key {
+            ssh_args.push(format!(
+                "-i {} -o IdentitiesOnly=yes",
+                quote(&expand_home(key))
+            ));
+        }
+        let mut config = Vec::new();
+        match (Transport::of(self.target()), self.proxy(defaults)) {
+            (_, ProxySetting::Inherit) | (Transport::Local, _) => {}
+            (Transport::Http, ProxySetting::Use(proxy)) => {
+                config.push(("http.proxy".to_string(), proxy))
+            }
+            // 空的 http.proxy 会让 git 忽略环境变量中的代理
+            (Transport::Http, ProxySetting::Bypass) => {
+                config.push(("http.proxy".to_string(), String::new()))
+            }
+            (Transport::Ssh, ProxySetting::Use(proxy)) => ssh_args.push(format!(
+                "-o {}",
+                quote(&format!(
+                    "ProxyCommand={}",
+                    proxy::ssh_proxy_command(&proxy)
+                ))
+            )),
+            (Transport::Ssh, ProxySetting::Bypass) => {
+                ssh_args.push("-o ProxyCommand=none".to_string())
+            }
+        }
+        config.extend(
+            self.git_config
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+
+        let ssh_command = match (&self.ssh_command, ssh_args.is_empty()) {
+            (Some(command), true) => Some(command.clone()),
+            (Some(command), false) => Some(format!("{} {}", command, ssh_args.join(" "))),
+            (None, false) => Some(format!("ssh {}", ssh_args.join(" "))),
+            (None, true) => None,
+        };
+        GitOverrides {
+            ssh_command,
+            config,
+        }
+    }
+
+    /// 用于检查地址是否互相包含的固定前缀
+    fn match_prefix(&self) -> String {
+        match &self.template {
+            Some(template) => template::literal_prefix(template, &self.vars),
+            None => self.base.clone(),
+        }
+    }
+
+    /// 地址中固定部分的长度，多个远程仓库都能匹配时越长越优先
+    fn specificity(&self) -> usize {
+        match &self.template {
+            Some(template) => template::fixed_len(template, &self.vars),
+            None => self.base.len(),
+        }
+    }
+
+    /// 判断 URL 是否由该远程仓库配置生成
+    pub fn matches_url(&self, url: &str) -> bool {
+        if let Some(template) = &self.template {
+            return template::matches(template, &self.vars, url);
+        }
+        let prefix = &self.base;
+        if prefix.is_empty() || !url.starts_with(prefix.as_str()) {
+            return false;
+        }
+        let remainder = &url[prefix.len()..];
+        remainder.is_empty()
+            || prefix.ends_with('/')
+            || prefix.ends_with(':')
+            || remainder.starts_with('/')
+            || remainder.starts_with(':')
+    }
+}
+
+impl Config {
+    /// 叠加项目级配置：先按名称覆盖或追加，再禁用，最后覆盖默认项
+    pub fn merge_repo(&mut self, repo: RepoConfig) {
+        // 先覆盖再禁用，同一文件中既定义又禁用的远程仓库以禁用为准
+        self.upsert_remotes(repo.remotes);
+        let (disabled, remotes): (Vec<Remote>, Vec<Remote>) = std::mem::take(&mut self.remotes)
+            .into_iter()
+            .partition(|remote| repo.disable.contains(&remote.name));
+        self.remotes = remotes;
+        self.disable(disabled, DisableReason::RepoDisabled);
+        self.defaults.merge(repo.defaults);
+        self.groups.extend(repo.groups);
+    }
+
+    /// 作为拉取地址的远程仓库：defaults.fetch_remote > primary 标记 > 第一个远程仓库
+    ///
+    /// fetch_remote 指向的仓库不存在（或已被禁用、不在分组中）时返回警告信息并继续按后续规则选择
+    pub fn fetch_remote(&self) -> (Option<&Remote>, Option<String>) {
+        let mut warning = None;
+        if let Some(name) = &self.defaults.fetch_remote {
+            match self.remotes.iter().find(|r| &r.name == name) {
+                Some(remote) => return (Some(remote), None),
+                None => {
+                    warning = Some(format!(
+                        "defaults.fetch_remote 指定的远程仓库 '{}' 不可用，改用其他远程仓库",
+                        name
+                    ))
+                }
+            }
+        }
+        let remote = self
+            .remotes
+            .iter()
+            .find(|r| r.primary)
+            .or_else(|| self.remotes.first());
+        (remote, warning)
+    }
+
+    /// 将 enabled = false 的远程仓库移入 disabled
+    pub fn split_disabled(&mut self) {
+        let (enabled, disabled): (Vec<Remote>, Vec<Remote>) = std::mem::take(&mut self.remotes)
+            .into_iter()
+            .partition(|remote| remote.enabled);
+        self.remotes = enabled;
+        self.disable(disabled, DisableReason::Disabled);
+    }
+
+    fn disable(&mut self, remotes: Vec<Remote>, reason: DisableReason) {
+        self.disabled.extend(
+            remotes
+                .into_iter()
+                .map(|remote| DisabledRemote { remote, reason }),
+        );
+    }
+
+    /// 叠加更高优先级的配置文件：按名称覆盖或追加远程仓库，覆盖分组与默认项
+    pub fn merge_config(&mut self, other: Config) {
+        self.upsert_remotes(other.remotes);
+        self.defaults.merge(other.defaults);
+        self.groups.extend(other.groups);
+    }
+
+    /// 按名称覆盖已有的远程仓库，不存在时追加
+    fn upsert_remotes(&mut self, remotes: Vec<Remote>) {
+        for new_remote in remotes {
+            match self
+                .remotes
+                .iter_mut()
+                .find(|remote| remote.name == new_remote.name)
+            {
+                Some(remote) => *remote = new_remote,
+                None => self.remotes.push(new_remote),
+            }
+        }
+    }
+
+    /// 获取分组包含的远程仓库名称
+    pub fn group_members(&self, group: &str) -> Result<&[String]> {
+        match self.groups.get(group) {
+            Some(members) => Ok(members),
+            None => {
+                let available: Vec<&str> = self.groups.keys().map(String::as_str).collect();
+                if available.is_empty() {
+                    bail!("未找到分组 '{}'，当前没有配置任何分组", group);
+                }
+                bail!("未找到分组 '{}'，可用分组: {}", group, available.join(", "));
+            }
+        }
+    }
+
+    /// 仅保留指定分组中的远程仓库
+    pub fn retain_group(&mut self, group: &str) -> Result<()> {
+        let members = self.group_members(group)?.to_vec();
+        self.remotes.retain(|remote| members.contains(&remote.name));
+        Ok(())
+    }
+
+    /// 在启用与禁用的远程仓库中按最长固定地址匹配 URL，返回匹配到的配置及其禁用原因
+    ///
+    /// 两者必须一起匹配：较短的禁用地址不能遮住较长的启用地址，反之亦然
+    pub fn match_url(&self, url: &str) -> Option<(&Remote, Option<DisableReason>)> {
+        let mut candidates: Vec<(&Remote, Option<DisableReason>)> = self
+            .remotes
+            .iter()
+            .map(|remote| (remote, None))
+            .chain(
+                self.disabled
+                    .iter()
+                    .map(|disabled| (&disabled.remote, Some(disabled.reason))),
+            )
+            .collect();
+        // 稳定排序，前缀长度相同时启用的远程仓库优先
+        candidates.sort_by_key(|(remote, _)| std::cmp::Reverse(remote.specificity()));
+        candidates
+            .into_iter()
+            .find(|(remote, _)| remote.matches_url(url))
+    }
+
+    /// 匹配 URL 对应的、已启用的远程仓库配置
+    pub fn match_remote(&self, url: &str) -> Option<&Remote> {
+        match self.match_url(url) {
+            Some((remote, None)) => Some(remote),
+            _ => None,
+        }
+    }
+
+    /// 匹配 URL 对应的、被禁用的远程仓库及其禁用原因
+    pub fn match_disabled(&self, url: &str) -> Option<(&Remote, DisableReason)> {
+        match self.match_url(url) {
+            Some((remote, Some(reason))) => Some((remote, reason)),
+            _ => None,
+        }
+    }
+
+    /// 匹配 URL 对应的显示名称，匹配失败时返回 "未命名"
+    pub fn display_name(&self, url: &str) -> String {
+        self.match_remote(url)
+            .map(|remote| remote.name.clone())
+            .unwrap_or_else(|| UNNAMED_REMOTE.to_string())
+    }
+}
+
+impl Defaults {
+    /// 用另一份默认配置中已设置的值覆盖当前值
+    pub fn merge(&mut self, other: Defaults) {
+        self.retry = other.retry.or(self.retry);
+        self.retry_delay = other.retry_delay.or(self.retry_delay);
+        self.timeout = other.timeout.or(self.timeout);
+        self.check_timeout = other.check_timeout.or(self.check_timeout);
+        self.alias = other.alias.or(self.alias.take());
+        self.group = other.group.or(self.group.take());
+        self.fetch_remote = other.fetch_remote.or(self.fetch_remote.take());
+        self.proxy = other.proxy.or(self.proxy.take());
+        self.no_proxy = other.no_proxy.or(self.no_proxy.take());
+    }
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    // 计算配置文件路径
+    // 显式指定的配置文件优先
+    if let Ok(path) = std::env::var("YEWPB_CONFIG") {
+        if !path.trim().is_empty() {
+            return Ok(PathBuf::from(path));
+        }
+    }
+
+    // 开发环境下将配置存到项目根目录的 .dev 文件夹
+    if std::env::var("YEWPB_ENV")
+        .map(|value| value.eq_ignore_ascii_case("dev"))
+        .unwrap_or(false)
+    {
+        let current_dir = std::env::current_dir().context("获取当前目录失败")?;
+        return Ok(current_dir.join(".dev").join("config.toml"));
+    }
+
+    // 使用系统推荐的配置目录，避免污染项目仓库
+    let project_dirs = ProjectDirs::from("com", "yewfence", "yewpb").context("获取配置目录失败")?;
+    Ok(project_dirs.config_dir().join("config.toml"))
+}
+
+pub fn load_config(path: &Path) -> Result<Config> {
+    // 读取本地配置，不存在时返回空配置
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("读取配置文件失败: {}", path.display()))?;
+    let config = toml::from_str(&content)
+        .with_context(|| format!("配置文件格式不合法: {}", path.display()))?;
+    Ok(config)
+}
+
+/// 从当前目录向上查找 git 仓库根目录
+pub fn find_repo_root() -> Option<PathBuf> {
+    let current_dir = std::env::current_dir().ok()?;
+    current_dir
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// 读取项目级配置文件，不存在时返回 None
+pub fn load_repo_config(path: &Path) -> Result<Option<RepoConfig>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("读取项目配置文件失败: {}", path.display()))?;
+    let config = toml::from_str(&content)
+        .with_context(|| format!("项目配置文件格式不合法: {}", path.display()))?;
+    Ok(Some(config))
+}
+
+/// 读取叠加系统配置、用户配置、项目配置与环境变量后的配置
+pub fn load_merged_config(path: &Path) -> Result<Config> {
+    Ok(load_layered_config(path)?.config)
+}
+
+/// 与 [`load_merged_config`] 相同，但项目配置从指定的仓库根目录读取
+pub fn load_merged_config_in(path: &Path, repo_root: &Path) -> Result<Config> {
+    Ok(load_layered_config_in(path, Some(repo_root))?.config)
+}
+
+/// 与 [`load_merged_config`] 相同，但只读取 sources 指定的来源
+pub fn load_merged_config_from(path: &Path, sources: &ConfigSources) -> Result<Config> {
+    Ok(load_layered_config_from(path, sources)?.config)
+}
+
+pub fn save_config(path: &Path, config: &Config) -> Result<()> {
+    // 保存配置到本地文件
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("创建配置目录失败: {}", parent.display()))?;
+    }
+    let content = toml::to_string_pretty(config).context("序列化配置失败")?;
+    save_config_content(path, &content)
+}
+
+/// 写入配置文件内容，覆盖前自动备份原有内容
+pub fn save_config_content(path: &Path, content: &str) -> Result<()> {
+    if path.exists() {
+        let old = fs::read_to_string(path)
+            .with_context(|| format!("读取配置文件失败: {}", path.display()))?;
+        if old == content {
+            return Ok(());
+        }
+        if !old.trim().is_empty() {
+            create_backup(path, &old)?;
+        }
+    }
+    write_atomic(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::fixtures::remote;
+
+    #[test]
+    fn repo_disable_wins_over_remote_in_the_same_file() {
+        let mut config = Config {
+            remotes: vec![remote("github", "https://github.com/me")],
+            ..Default::default()
+        };
+        config.merge_repo(RepoConfig {
+            remotes: vec![remote("gitlab", "https://gitlab.com/me")],
+            disable: vec!["github".to_string(), "gitlab".to_string()],
+            ..Default::default()
+        });
+
+        assert!(config.remotes.is_empty());
+        let disabled: Vec<(&str, DisableReason)> = config
+            .disabled
+            .iter()
+            .map(|d| (d.remote.name.as_str(), d.reason))
+            .collect();
+        assert_eq!(
+            disabled,
+            vec![
+                ("github", DisableReason::RepoDisabled),
+                ("gitlab", DisableReason::RepoDisabled)
+            ]
+        );
+    }
+
+    #[test]
+    fn isolated_sources_read_only_user_and_repo_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("config.toml");
+        let system = dir.path().join("system.toml");
+        fs::write(
+            &user,
+            "[[remotes]]\nname = \"a\"\nbase = \"https://a.example.com/\"\n",
+        )
+        .unwrap();
+        fs::write(
+            &system,
+            "[[remotes]]\nname = \"s\"\nbase = \"https://s.example.com/\"\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join(REPO_CONFIG_FILE), "[defaults]\nretry = 7\n").unwrap();
+
+        let config = load_merged_config_from(&user, &ConfigSources::isolated(dir.path())).unwrap();
+        let names: Vec<&str> = config.remotes.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["a"]);
+        assert_eq!(config.defaults.retry, Some(7));
+
+        let sources = ConfigSources {
+            system: Some(system),
+            ..ConfigSources::isolated(dir.path())
+        };
+        let config = load_merged_config_from(&user, &sources).unwrap();
+        let names: Vec<&str> = config.remotes.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["s", "a"]);
+    }
+
+    #[test]
+    fn longest_base_wins_across_enabled_and_disabled() {
+        let config = Config {
+            remotes: vec![remote("org", "https://github.com/org")],
+            disabled: vec![DisabledRemote {
+                remote: Remote {
+                    enabled: false,
+                    ..remote("github", "https://github.com")
+                },
+                reason: DisableReason::Disabled,
+            }],
+            ..Default::default()
+        };
+
+        let org = "https://github.com/org/demo.git";
+        assert_eq!(config.match_remote(org).unwrap().name, "org");
+        assert!(config.match_disabled(org).is_none());
+
+        let other = "https://github.com/me/demo.git";
+        assert!(config.match_remote(other).is_none());
+        let (remote, reason) = config.match_disabled(other).unwrap();
+        assert_eq!(
+            (remote.name.as_str(), reason),
+            ("github", DisableReason::Disabled)
+        );
+        assert_eq!(config.display_name(other), UNNAMED_REMOTE);
+    }
+
+    #[test]
+    fn template_starting_with_var_matches_its_urls() {
+        let azure = Remote {
+            template: Some("{owner}/{group}/_git/{repo}".to_string()),
+            vars: [("owner", "https://dev.azure.com/org"), ("group", "proj")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..remote("azure", "")
+        };
+        let config = Config {
+            remotes: vec![remote("dev", "https://dev.azure.com"), azure],
+            ..Default::default()
+        };
+
+        let url = config.remotes[1].build_url("demo", "demo").unwrap();
+        assert_eq!(url, "https://dev.azure.com/org/proj/_git/demo");
+        assert_eq!(config.display_name(&url), "azure");
+        assert_eq!(
+            config.display_name("https://dev.azure.com/other/demo.git"),
+            "dev"
+        );
+    }
+}
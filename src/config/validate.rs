@@ -0,0 +1,859 @@
+use super::keys::find_key;
+use super::layers::LayeredConfig;
+use super::{Config, Remote, RepoConfig, DEFAULT_KEYS};
+use crate::proxy::{self, Transport};
+use crate::secret;
+use crate::template::{self, TemplateContext, VAR_PLACEHOLDERS};
+use crate::utils::expand_home;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use toml_edit::{ImDocument, Item, TableLike};
+
+/// 顶层允许的键
+const TOP_LEVEL_KEYS: &[&str] = &["remotes", "defaults", "groups"];
+/// 项目配置额外允许的顶层键
+const REPO_TOP_LEVEL_KEYS: &[&str] = &["disable"];
+/// [[remotes]] 中允许的键，url 是 base 的别名
+const REMOTE_KEYS: &[&str] = &[
+    "name",
+    "enabled",
+    "primary",
+    "base",
+    "url",
+    "template",
+    "vars",
+    "rename",
+    "timeout",
+    "retry",
+    "retry_delay",
+    "allow_force",
+    "extra_args",
+    "skip_check",
+    "ssh_key",
+    "ssh_command",
+    "git_config",
+    "proxy",
+    "no_proxy",
+    "note",
+];
+/// rename 表中允许的键
+const RENAME_KEYS: &[&str] = &["name", "prefix", "suffix", "case", "pattern", "replace"];
+
+/// 诊断的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// 配置无法正常使用
+    Error,
+    /// 配置可以使用，但很可能不符合预期
+    Warning,
+}
+
+/// 一条校验诊断
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 所在行号，从 1 开始
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "错误",
+            Severity::Warning => "警告",
+        };
+        match self.line {
+            Some(line) => write!(f, "{} (第 {} 行): {}", level, line, self.message),
+            None => write!(f, "{}: {}", level, self.message),
+        }
+    }
+}
+
+/// 一个配置文件的校验结果
+#[derive(Debug, Default)]
+pub struct Report {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    /// 错误数量
+    pub fn errors(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    /// 警告数量
+    pub fn warnings(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+
+    fn push(&mut self, severity: Severity, line: Option<usize>, message: String) {
+        // 底层错误信息可能跨多行，合并为一行便于阅读
+        let message = message
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.diagnostics.push(Diagnostic {
+            severity,
+            line,
+            message,
+        });
+    }
+
+    fn error(&mut self, line: Option<usize>, message: String) {
+        self.push(Severity::Error, line, message);
+    }
+
+    fn warning(&mut self, line: Option<usize>, message: String) {
+        self.push(Severity::Warning, line, message);
+    }
+}
+
+/// 配置文件的类型，决定允许的键与检查规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// 系统或用户配置
+    Global,
+    /// 仓库根目录下的 .yewpb.toml / .yewpb.local.toml
+    Repo,
+}
+
+impl FileKind {
+    /// 根据文件名推断配置类型
+    pub fn from_path(path: &Path) -> Self {
+        match path.file_name().and_then(|name| name.to_str()) {
+            Some(super::REPO_CONFIG_FILE) | Some(super::REPO_LOCAL_CONFIG_FILE) => FileKind::Repo,
+            _ => FileKind::Global,
+        }
+    }
+}
+
+/// 读取并校验配置文件
+pub fn validate_file(path: &Path) -> Result<Report> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("读取配置文件失败: {}", path.display()))?;
+    Ok(validate_str(&content, FileKind::from_path(path)))
+}
+
+/// 校验配置文件内容
+pub fn validate_str(content: &str, kind: FileKind) -> Report {
+    let mut report = Report::default();
+    let lines = LineIndex::new(content);
+
+    // 语法错误时无法继续检查
+    let doc = match ImDocument::parse(content) {
+        Ok(doc) => doc,
+        Err(err) => {
+            let line = err.span().map(|span| lines.line(span.start));
+            report.error(line, format!("TOML 语法错误: {}", err.message().trim()));
+            return report;
+        }
+    };
+    check_unknown_keys(&doc, kind, &lines, &mut report);
+
+    // 类型错误由反序列化报告
+    let parsed = match kind {
+        FileKind::Global => toml::from_str::<Config>(content),
+        FileKind::Repo => toml::from_str::<RepoConfig>(content).map(repo_as_config),
+    };
+    let config = match parsed {
+        Ok(config) => config,
+        Err(err) => {
+            let line = err.span().map(|span| lines.line(span.start));
+            report.error(line, err.message().trim().to_string());
+            return report;
+        }
+    };
+
+    let remote_lines = remote_lines(&doc, &lines);
+    let line_of = |i: usize| remote_lines.get(i).copied().flatten();
+    check_remotes(&config.remotes, kind, &line_of, &mut report);
+    check_defaults_and_groups(&config, &doc, kind, &lines, &mut report);
+    report.diagnostics.sort_by_key(|d| d.line);
+    report
+}
+
+/// 校验叠加所有配置层后的配置，检查单个文件中无法发现的冲突
+pub fn validate_layered(layered: &LayeredConfig) -> Report {
+    let mut report = Report::default();
+    let config = &layered.config;
+    // 设置了 fetch_remote 时不使用 primary 标记
+    if config.defaults.fetch_remote.is_some() {
+        return report;
+    }
+    let origin = |remote: &Remote| {
+        layered
+            .remote_origins
+            .get(&remote.name)
+            .map(ToString::to_string)
+            .unwrap_or_default()
+    };
+    let primaries: Vec<&Remote> = config.remotes.iter().filter(|r| r.primary).collect();
+    if let Some((first, rest)) = primaries.split_first() {
+        for remote in rest {
+            report.error(
+                None,
+                format!(
+                    "远程仓库 '{}'（{}）与 '{}'（{}）同时设置了 primary = true，只能有一个拉取地址，可在项目配置中设置 fetch_remote",
+                    remote.name,
+                    origin(remote),
+                    first.name,
+                    origin(first)
+                ),
+            );
+        }
+    }
+    report
+}
+
+/// 项目配置的 remotes、defaults 与 groups 按全局配置的规则检查
+fn repo_as_config(repo: RepoConfig) -> Config {
+    Config {
+        remotes: repo.remotes,
+        defaults: repo.defaults,
+        groups: repo.groups,
+        disabled: Vec::new(),
+    }
+}
+
+/// 检查未知的键，通常是拼写错误
+fn check_unknown_keys(
+    doc: &ImDocument<&str>,
+    kind: FileKind,
+    lines: &LineIndex,
+    report: &mut Report,
+) {
+    let root = doc.as_table();
+    for (key, _) in root.iter() {
+        let allowed = TOP_LEVEL_KEYS.contains(&key)
+            || (kind == FileKind::Repo && REPO_TOP_LEVEL_KEYS.contains(&key));
+        if !allowed {
+            report.warning(
+                key_line(root, key, lines),
+                format!("未知的顶层配置项 '{}'，将被忽略", key),
+            );
+        }
+    }
+
+    if let Some(defaults) = root.get("defaults").and_then(Item::as_table_like) {
+        for (key, _) in defaults.iter() {
+            if find_key(key).is_err() || key.contains('-') {
+                report.warning(
+                    key_line(defaults, key, lines),
+                    format!(
+                        "[defaults] 中未知的配置项 '{}'，可用配置项: {}",
+                        key,
+                        DEFAULT_KEYS
+                            .iter()
+                            .map(|k| k.name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                );
+            }
+        }
+    }
+
+    for table in remote_tables(root.get("remotes")) {
+        for (key, _) in table.iter() {
+            if !REMOTE_KEYS.contains(&key) {
+                report.warning(
+                    key_line(table, key, lines),
+                    format!("[[remotes]] 中未知的配置项 '{}'，将被忽略", key),
+                );
+            }
+        }
+        if let Some(rename) = table.get("rename").and_then(Item::as_table_like) {
+            for (key, _) in rename.iter() {
+                if !RENAME_KEYS.contains(&key) {
+                    report.warning(
+                        key_line(rename, key, lines),
+                        format!("rename 中未知的配置项 '{}'，将被忽略", key),
+                    );
+                }
+            }
+        }
+        if let Some(vars) = table.get("vars").and_then(Item::as_table_like) {
+            for (key, _) in vars.iter() {
+                if !VAR_PLACEHOLDERS.contains(&key) {
+                    report.warning(
+                        key_line(vars, key, lines),
+                        format!(
+                            "vars 中未知的变量 '{}'，可用变量: {}",
+                            key,
+                            VAR_PLACEHOLDERS.join(", ")
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// 检查远程仓库：名称、地址、模板与映射规则
+fn check_remotes(
+    remotes: &[Remote],
+    kind: FileKind,
+    line_of: &dyn Fn(usize) -> Option<usize>,
+    report: &mut Report,
+) {
+    for (i, remote) in remotes.iter().enumerate() {
+        let line = line_of(i);
+        if remote.name.trim().is_empty() {
+            report.error(line, format!("第 {} 个远程仓库缺少名称", i + 1));
+        }
+        if let Some(j) = remotes[..i].iter().position(|r| r.name == remote.name) {
+            report.error(
+                line,
+                format!(
+                    "远程仓库名称 '{}' 重复，与第 {} 行的配置冲突",
+                    remote.name,
+                    display_line(line_of(j))
+                ),
+            );
+        }
+
+        match &remote.template {
+            Some(template) => check_template(remote, template, line, report),
+            None if remote.base.is_empty() => {
+                // 项目配置中的同名仓库可能只是覆盖，但仍然需要完整地址
+                let hint = match kind {
+                    FileKind::Repo => "（项目配置中的远程仓库会整体覆盖全局同名配置）",
+                    FileKind::Global => "",
+                };
+                report.error(
+                    line,
+                    format!("远程仓库 '{}' 缺少 base 或 template{}", remote.name, hint),
+                );
+            }
+            None => {
+                if !looks_like_url(&remote.base) {
+                    report.error(
+                        line,
+                        format!(
+                            "远程仓库 '{}' 的地址 '{}' 无法识别，应为 https://host/path、ssh://host/path 或 user@host:path 形式",
+                            remote.name, remote.base
+                        ),
+                    );
+                }
+            }
+        }
+
+        if let Err(err) = remote.rename.validate() {
+            report.error(
+                line,
+                format!(
+                    "远程仓库 '{}' 的仓库名称映射规则无效: {:#}",
+                    remote.name, err
+                ),
+            );
+        }
+        check_secrets(remote, line, report);
+        check_git_overrides(remote, line, report);
+    }
+
+    check_overlapping(remotes, line_of, report);
+    check_primary(remotes, line_of, report);
+}
+
+/// 最多只能有一个 primary 远程仓库
+fn check_primary(
+    remotes: &[Remote],
+    line_of: &dyn Fn(usize) -> Option<usize>,
+    report: &mut Report,
+) {
+    let primaries: Vec<usize> = (0..remotes.len()).filter(|&i| remotes[i].primary).collect();
+    if let Some((&first, rest)) = primaries.split_first() {
+        for &i in rest {
+            report.error(
+                line_of(i),
+                format!(
+                    "远程仓库 '{}' 与 '{}'（第 {} 行）同时设置了 primary = true，只能有一个拉取地址",
+                    remotes[i].name,
+                    remotes[first].name,
+                    display_line(line_of(first))
+                ),
+            );
+        }
+    }
+}
+
+/// 检查地址中的 ${VAR} 占位符与明文密码
+fn check_secrets(remote: &Remote, line: Option<usize>, report: &mut Report) {
+    let url = remote.target();
+    match secret::placeholders(url) {
+        Err(err) => report.error(line, format!("远程仓库 '{}': {}", remote.name, err)),
+        Ok(names) if !names.is_empty() && !secret::only_in_userinfo(url) => report.warning(
+            line,
+            format!(
+                "远程仓库 '{}' 的 ${{VAR}} 不在 https 地址的用户信息部分，展开后的值会出现在 git 命令参数中",
+                remote.name
+            ),
+        ),
+        Ok(_) => {}
+    }
+    if secret::has_plain_password(url) {
+        report.warning(
+            line,
+            format!(
+                "远程仓库 '{}' 的地址包含明文密码，会被写入 .git/config 和导出文件，建议改用 ${{VAR}} 引用环境变量",
+                remote.name
+            ),
+        );
+    }
+}
+
+/// 检查 ssh_key、git_config 与代理
+fn check_git_overrides(remote: &Remote, line: Option<usize>, report: &mut Report) {
+    if let Some(url) = &remote.proxy {
+        if let Err(err) = proxy::validate(url) {
+            report.error(line, format!("远程仓库 '{}': {}", remote.name, err));
+        } else if proxy::has_credentials(url) && Transport::of(remote.target()) == Transport::Ssh {
+            report.warning(
+                line,
+                format!(
+                    "远程仓库 '{}' 使用 SSH 地址，代理中的认证信息不会传给 ProxyCommand",
+                    remote.name
+                ),
+            );
+        }
+        if remote.no_proxy {
+            report.warning(
+                line,
+                format!(
+                    "远程仓库 '{}' 同时设置了 proxy 与 no_proxy，proxy 将被忽略",
+                    remote.name
+                ),
+            );
+        }
+    }
+    if let Some(key) = &remote.ssh_key {
+        if !Path::new(&expand_home(key)).exists() {
+            report.warning(
+                line,
+                format!("远程仓库 '{}' 的 ssh_key 文件不存在: {}", remote.name, key),
+            );
+        }
+    }
+    for key in remote.git_config.keys() {
+        let valid = key
+            .split_once('.')
+            .is_some_and(|(section, name)| !section.is_empty() && !name.is_empty())
+            && !key.contains(['=', ' ']);
+        if !valid {
+            report.error(
+                line,
+                format!(
+                    "远程仓库 '{}' 的 git_config 键 '{}' 无效，应为 section.key 形式",
+                    remote.name, key
+                ),
+            );
+        }
+    }
+}
+
+/// 检查 URL 模板的语法与变量
+fn check_template(remote: &Remote, template: &str, line: Option<usize>, report: &mut Report) {
+    if let Err(err) = template::validate(template) {
+        report.error(line, format!("远程仓库 '{}': {}", remote.name, err));
+        return;
+    }
+    let ctx = TemplateContext {
+        repo: "repo",
+        dir: "dir",
+        vars: &remote.vars,
+    };
+    match template::render(template, &ctx) {
+        Ok(url) if !looks_like_url(&url) => report.error(
+            line,
+            format!(
+                "远程仓库 '{}' 的模板生成的地址 '{}' 无法识别",
+                remote.name, url
+            ),
+        ),
+        Ok(_) => {}
+        Err(err) => report.error(line, format!("远程仓库 '{}': {}", remote.name, err)),
+    }
+}
+
+/// 检查重复或互相包含的地址，它们会影响按最长前缀匹配远程仓库
+fn check_overlapping(
+    remotes: &[Remote],
+    line_of: &dyn Fn(usize) -> Option<usize>,
+    report: &mut Report,
+) {
+    let prefixes: Vec<String> = remotes.iter().map(|r| r.match_prefix()).collect();
+    let same = |a: &str, b: &str| a.trim_end_matches('/') == b.trim_end_matches('/');
+    // 与前面的地址相同的仓库只报告一次重复，不再参与包含关系检查
+    let duplicated: Vec<bool> = (0..remotes.len())
+        .map(|i| (0..i).any(|j| same(&prefixes[i], &prefixes[j])))
+        .collect();
+    for i in 0..remotes.len() {
+        for j in 0..i {
+            let (a, b) = (&prefixes[i], &prefixes[j]);
+            if a.is_empty() || b.is_empty() {
+                continue;
+            }
+            if same(a, b) {
+                report.error(
+                    line_of(i),
+                    format!(
+                        "远程仓库 '{}' 与 '{}'（第 {} 行）的地址相同，推送结果无法区分",
+                        remotes[i].name,
+                        remotes[j].name,
+                        display_line(line_of(j))
+                    ),
+                );
+            } else if duplicated[i] || duplicated[j] {
+                continue;
+            } else if let Some((longer, shorter)) = nested(i, j, a, b) {
+                report.warning(
+                    line_of(i),
+                    format!(
+                        "远程仓库 '{}' 的地址包含在 '{}' 的地址之下，匹配时优先使用更长的 '{}'",
+                        remotes[longer].name, remotes[shorter].name, remotes[longer].name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// 若一个地址以另一个地址加分隔符开头，返回 (更长者, 更短者) 的下标
+fn nested(i: usize, j: usize, a: &str, b: &str) -> Option<(usize, usize)> {
+    let contains = |longer: &str, shorter: &str| {
+        let shorter = shorter.trim_end_matches('/');
+        longer
+            .strip_prefix(shorter)
+            .is_some_and(|rest| rest.starts_with('/') || rest.starts_with(':'))
+    };
+    if contains(a, b) {
+        Some((i, j))
+    } else if contains(b, a) {
+        Some((j, i))
+    } else {
+        None
+    }
+}
+
+/// 检查默认配置项的取值范围与分组引用
+fn check_defaults_and_groups(
+    config: &Config,
+    doc: &ImDocument<&str>,
+    kind: FileKind,
+    lines: &LineIndex,
+    report: &mut Report,
+) {
+    let root = doc.as_table();
+    let defaults_table = root.get("defaults").and_then(Item::as_table_like);
+    for key in DEFAULT_KEYS {
+        if let Some(value) = config.defaults.get(key.name) {
+            if let Err(err) = key.validate(&value) {
+                let line = defaults_table.and_then(|t| key_line(t, key.name, lines));
+                report.error(line, err.to_string());
+            }
+        }
+    }
+
+    // 项目配置中的分组可能引用全局配置中的远程仓库，无法单独检查
+    if kind == FileKind::Repo {
+        return;
+    }
+    let groups_table = root.get("groups").and_then(Item::as_table_like);
+    for (name, members) in &config.groups {
+        let line = groups_table.and_then(|t| key_line(t, name, lines));
+        if members.is_empty() {
+            report.warning(line, format!("分组 '{}' 为空", name));
+        }
+        for member in members {
+            if !config.remotes.iter().any(|r| &r.name == member) {
+                report.warning(
+                    line,
+                    format!("分组 '{}' 中的远程仓库 '{}' 不存在", name, member),
+                );
+            }
+        }
+    }
+    if let Some(group) = &config.defaults.group {
+        if !config.groups.contains_key(group) {
+            let line = defaults_table.and_then(|t| key_line(t, "group", lines));
+            report.warning(line, format!("默认分组 '{}' 不存在", group));
+        }
+    }
+    if let Some(name) = &config.defaults.fetch_remote {
+        if !config.remotes.iter().any(|r| &r.name == name) {
+            let line = defaults_table.and_then(|t| key_line(t, "fetch_remote", lines));
+            report.warning(
+                line,
+                format!("defaults.fetch_remote 指定的远程仓库 '{}' 不存在", name),
+            );
+        }
+    }
+}
+
+/// 粗略判断地址格式：scheme://host/...、user@host:path 或 host:path
+fn looks_like_url(url: &str) -> bool {
+    if url.chars().any(char::is_whitespace) {
+        return false;
+    }
+    if let Some((scheme, rest)) = url.split_once("://") {
+        let known = ["http", "https", "ssh", "git", "file"];
+        if !known.contains(&scheme) {
+            return false;
+        }
+        // file:///path 没有主机名
+        return scheme == "file" || rest.split('/').next().is_some_and(|host| !host.is_empty());
+    }
+    // scp 风格：[user@]host:path，path 可以为空（仓库位于用户根目录）
+    match url.split_once(':') {
+        Some((host, _)) => {
+            let host = host.rsplit('@').next().unwrap_or(host);
+            !host.is_empty() && !host.contains('/')
+        }
+        None => false,
+    }
+}
+
+/// 依次列出 remotes 中的每个表，兼容 [[remotes]] 与内联数组两种写法
+fn remote_tables(item: Option<&Item>) -> Vec<&dyn TableLike> {
+    match item {
+        Some(Item::ArrayOfTables(array)) => array.iter().map(|t| t as &dyn TableLike).collect(),
+        Some(Item::Value(value)) => match value.as_array() {
+            Some(array) => array
+                .iter()
+                .filter_map(|v| v.as_inline_table())
+                .map(|t| t as &dyn TableLike)
+                .collect(),
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// 每个远程仓库在文件中的行号，优先取 name 所在行
+fn remote_lines(doc: &ImDocument<&str>, lines: &LineIndex) -> Vec<Option<usize>> {
+    let item = doc.as_table().get("remotes");
+    let mut result: Vec<Option<usize>> = remote_tables(item)
+        .into_iter()
+        .map(|table| key_line(table, "name", lines))
+        .collect();
+    // 缺少 name 时退回到 [[remotes]] 表头所在行
+    if let Some(Item::ArrayOfTables(array)) = item {
+        for (line, table) in result.iter_mut().zip(array.iter()) {
+            if line.is_none() {
+                *line = table.span().map(|span| lines.line(span.start));
+            }
+        }
+    }
+    result
+}
+
+fn key_line(table: &dyn TableLike, key: &str, lines: &LineIndex) -> Option<usize> {
+    table
+        .key(key)
+        .and_then(|key| key.span())
+        .map(|span| lines.line(span.start))
+}
+
+fn display_line(line: Option<usize>) -> String {
+    line.map(|l| l.to_string())
+        .unwrap_or_else(|| "?".to_string())
+}
+
+/// 字节偏移到行号的映射
+struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(content: &str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex { starts }
+    }
+
+    /// 偏移所在的行号，从 1 开始
+    fn line(&self, offset: usize) -> usize {
+        match self.starts.binary_search(&offset) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Origin;
+    use std::path::PathBuf;
+
+    fn diagnostics(report: &Report) -> Vec<(Severity, Option<usize>, &str)> {
+        report
+            .diagnostics
+            .iter()
+            .map(|d| (d.severity, d.line, d.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn valid_config_has_no_diagnostics() {
+        let report = validate_str(
+            r#"
+[[remotes]]
+name = "github"
+base = "git@github.com:me"
+
+[defaults]
+retry = 2
+"#,
+            FileKind::Global,
+        );
+        assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+    }
+
+    #[test]
+    fn reports_duplicate_names_on_the_name_line() {
+        let report = validate_str(
+            r#"[[remotes]]
+name = "github"
+base = "git@github.com:me"
+
+[[remotes]]
+name = "github"
+base = "git@gitlab.com:me"
+"#,
+            FileKind::Global,
+        );
+        let diagnostics = diagnostics(&report);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        let (severity, line, message) = diagnostics[0];
+        assert_eq!(severity, Severity::Error);
+        assert_eq!(line, Some(6));
+        assert!(message.contains("'github' 重复，与第 2 行"), "{}", message);
+    }
+
+    #[test]
+    fn reports_same_and_nested_bases() {
+        let report = validate_str(
+            r#"[[remotes]]
+name = "a"
+base = "https://github.com/me/"
+
+[[remotes]]
+name = "b"
+base = "https://github.com/me"
+
+[[remotes]]
+name = "c"
+base = "https://github.com/me/team"
+"#,
+            FileKind::Global,
+        );
+        let diagnostics = diagnostics(&report);
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].0, Severity::Error);
+        assert_eq!(diagnostics[0].1, Some(6));
+        assert!(diagnostics[0].2.contains("'b' 与 'a'（第 2 行）的地址相同"));
+        // b 与 a 重复，只报告 c 包含在 a 之下
+        assert_eq!(diagnostics[1].0, Severity::Warning);
+        assert_eq!(diagnostics[1].1, Some(10));
+        assert!(diagnostics[1].2.contains("'c' 的地址包含在 'a' 的地址之下"));
+    }
+
+    #[test]
+    fn reports_unknown_keys_on_their_lines() {
+        let report = validate_str(
+            r#"colour = "red"
+
+[defaults]
+retries = 3
+
+[[remotes]]
+name = "github"
+bsae = "git@github.com:me"
+template = "git@github.com:{owner}/{repo}.git"
+vars = { owner = "me", team = "x" }
+rename = { nmae = "other" }
+"#,
+            FileKind::Global,
+        );
+        let diagnostics = diagnostics(&report);
+        let lines: Vec<Option<usize>> = diagnostics.iter().map(|d| d.1).collect();
+        assert_eq!(
+            lines,
+            vec![Some(1), Some(4), Some(8), Some(10), Some(11)],
+            "{:?}",
+            diagnostics
+        );
+        assert!(diagnostics.iter().all(|d| d.0 == Severity::Warning));
+        assert!(diagnostics[0].2.contains("未知的顶层配置项 'colour'"));
+        assert!(diagnostics[1]
+            .2
+            .contains("[defaults] 中未知的配置项 'retries'"));
+        assert!(diagnostics[2]
+            .2
+            .contains("[[remotes]] 中未知的配置项 'bsae'"));
+        assert!(diagnostics[3].2.contains("vars 中未知的变量 'team'"));
+        assert!(diagnostics[4].2.contains("rename 中未知的配置项 'nmae'"));
+    }
+
+    #[test]
+    fn reports_primary_set_in_different_layers() {
+        let user = PathBuf::from("/home/me/config.toml");
+        let repo = PathBuf::from("/src/demo/.yewpb.toml");
+        let remote = |name: &str| Remote {
+            name: name.to_string(),
+            base: format!("https://{}.com/me", name),
+            primary: true,
+            ..Default::default()
+        };
+        let mut layered = LayeredConfig {
+            config: Config {
+                remotes: vec![remote("github"), remote("gitlab")],
+                ..Default::default()
+            },
+            layers: Vec::new(),
+            remote_origins: [
+                ("github".to_string(), Origin::User(user)),
+                ("gitlab".to_string(), Origin::Repo(repo)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let report = validate_layered(&layered);
+        assert_eq!(report.errors(), 1);
+        let message = &report.diagnostics[0].message;
+        assert!(message.contains("'gitlab'（项目配置"), "{}", message);
+        assert!(message.contains("'github'（用户配置"), "{}", message);
+
+        layered.config.defaults.fetch_remote = Some("gitlab".to_string());
+        assert!(validate_layered(&layered).diagnostics.is_empty());
+    }
+
+    #[test]
+    fn repo_files_allow_disable_and_syntax_errors_have_lines() {
+        let report = validate_str("disable = [\"github\"]\n", FileKind::Repo);
+        assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+        let report = validate_str("disable = [\"github\"]\n", FileKind::Global);
+        assert_eq!(report.warnings(), 1);
+
+        let report = validate_str("[[remotes]]\nname = \"a\"\nbase = \n", FileKind::Global);
+        let diagnostics = diagnostics(&report);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].1, Some(3));
+        assert!(diagnostics[0].2.starts_with("TOML 语法错误"));
+    }
+}
@@ -0,0 +1,636 @@
+use crate::config::{Config, Remote, ResolvedDefaults, UNNAMED_REMOTE};
+use crate::git::{GitBackend, GitOverrides, PushOptions, RetryConfig};
+use anyhow::Result;
+use rayon::prelude::*;
+use std::thread;
+use std::time::Duration;
+
+/// 命令行指定的重试参数，未指定时依次使用远程仓库配置、默认配置和内置默认值
+#[derive(Debug, Clone, Default)]
+pub struct RetryArgs {
+    /// 最大重试次数
+    pub retry: Option<u32>,
+    /// 重试间隔（毫秒）
+    pub retry_delay: Option<u64>,
+    /// 超时时间（秒）
+    pub timeout: Option<u64>,
+}
+
+/// 推送目标过滤条件
+#[derive(Debug, Clone, Default)]
+pub struct PushFilter {
+    /// 仅推送到这些名称的仓库
+    pub only: Vec<String>,
+    /// 排除这些名称的仓库
+    pub except: Vec<String>,
+    /// 仅推送到该分组中的仓库
+    pub group: Option<String>,
+}
+
+/// 一次推送请求的全部参数
+#[derive(Debug, Clone, Default)]
+pub struct PushRequest {
+    pub filter: PushFilter,
+    pub options: PushOptions,
+    pub retry: RetryArgs,
+    /// 跳过所有仓库的连接验证
+    pub skip_check: bool,
+}
+
+/// 单个仓库的推送目标，包含该仓库最终生效的推送策略
+#[derive(Debug, Clone)]
+pub struct PushTarget {
+    pub url: String,
+    /// 配置中的远程仓库名称，匹配失败时为 "未命名"
+    pub name: String,
+    /// 该仓库生效的重试与超时配置
+    pub retry: RetryConfig,
+    /// 该仓库是否跳过连接验证
+    pub skip_check: bool,
+    /// 该仓库生效的推送选项（已追加远程仓库的额外参数）
+    pub options: PushOptions,
+    /// 该仓库的 ssh 命令与 git -c 配置
+    pub git: GitOverrides,
+    /// 拒绝推送的原因，设置后不会执行推送
+    pub refused: Option<String>,
+}
+
+impl PushTarget {
+    /// 将要执行的完整命令，用于 dry-run 展示；地址中的 ${VAR} 保持原样
+    pub fn command_line(&self, branch: &str) -> String {
+        let mut parts = vec![self.git.describe(), "push".to_string()];
+        if self.options.force {
+            parts.push("--force".to_string());
+        }
+        if self.options.force_with_lease {
+            parts.push("--force-with-lease".to_string());
+        }
+        if self.options.set_upstream {
+            parts.push("--set-upstream".to_string());
+        }
+        if self.options.tags {
+            parts.push("--tags".to_string());
+        }
+        parts.push(self.url.clone());
+        parts.push(branch.to_string());
+        parts.extend(self.options.extra_args.iter().cloned());
+        parts.join(" ")
+    }
+}
+
+/// 因禁用而跳过的远程仓库
+#[derive(Debug, Clone)]
+pub struct SkippedRemote {
+    pub name: String,
+    pub reason: String,
+}
+
+/// 单个仓库的最终推送状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushStatus {
+    /// 推送成功
+    Success,
+    /// 达到最大重试次数后仍然失败
+    Failed,
+    /// 受保护，拒绝强制推送
+    Refused,
+}
+
+/// 单个仓库的推送结果
+#[derive(Debug, Clone)]
+pub struct PushOutcome {
+    pub name: String,
+    pub url: String,
+    pub status: PushStatus,
+    /// 实际尝试的次数
+    pub attempts: u32,
+    /// 最后一次失败或拒绝的原因
+    pub error: Option<String>,
+}
+
+/// 一次推送的全部结果，顺序与推送目标一致
+#[derive(Debug, Clone, Default)]
+pub struct PushReport {
+    pub outcomes: Vec<PushOutcome>,
+}
+
+impl PushReport {
+    pub fn with_status(&self, status: PushStatus) -> impl Iterator<Item = &PushOutcome> {
+        self.outcomes.iter().filter(move |o| o.status == status)
+    }
+
+    /// 是否所有仓库都推送成功
+    pub fn is_success(&self) -> bool {
+        self.outcomes
+            .iter()
+            .all(|o| o.status == PushStatus::Success)
+    }
+}
+
+/// 单次尝试的结束方式
+#[derive(Debug, Clone)]
+pub enum AttemptEnd {
+    /// 推送成功
+    Pushed,
+    /// 连接验证未通过
+    Unreachable,
+    /// 连接验证出错
+    CheckFailed(String),
+    /// 推送失败
+    PushFailed(String),
+}
+
+impl AttemptEnd {
+    fn error(&self) -> Option<String> {
+        match self {
+            AttemptEnd::Pushed => None,
+            AttemptEnd::Unreachable => Some("远程仓库无法访问".to_string()),
+            AttemptEnd::CheckFailed(err) => Some(format!("检查失败: {}", err)),
+            AttemptEnd::PushFailed(err) => Some(err.clone()),
+        }
+    }
+}
+
+/// 推送过程中的事件，index 为推送目标的下标
+#[derive(Debug, Clone)]
+pub enum PushEvent<'a> {
+    /// 开始一轮推送，round 为 0 表示首轮，之后为重试
+    RoundStarted { round: u32, pending: &'a [usize] },
+    /// 重试前等待
+    Waiting { index: usize, delay_ms: u64 },
+    /// 正在验证连接
+    Checking { index: usize },
+    /// 正在推送
+    Pushing { index: usize },
+    /// 本次尝试结束
+    Finished { index: usize, end: &'a AttemptEnd },
+    /// 本轮推送结束
+    RoundFinished { round: u32 },
+}
+
+/// 接收推送事件，用于展示进度；事件可能来自多个线程
+pub trait PushObserver: Sync {
+    fn on_event(&self, event: PushEvent<'_>);
+}
+
+/// 不关心进度时使用
+impl PushObserver for () {
+    fn on_event(&self, _event: PushEvent<'_>) {}
+}
+
+/// 多仓库推送引擎：按配置解析每个仓库的推送策略，并发推送并按各自的策略重试
+///
+/// 引擎本身不输出任何内容，进度通过 [`PushObserver`] 通知，结果以 [`PushReport`] 返回
+#[derive(Debug, Clone)]
+pub struct PushEngine {
+    branch: String,
+    targets: Vec<PushTarget>,
+    skipped: Vec<SkippedRemote>,
+}
+
+impl PushEngine {
+    /// 根据 yewpb remote 的推送地址生成推送计划
+    ///
+    /// 被禁用的仓库记录在 [`skipped`](Self::skipped) 中，不符合过滤条件的仓库直接忽略
+    pub fn plan(
+        config: &Config,
+        defaults: &ResolvedDefaults,
+        urls: Vec<String>,
+        branch: &str,
+        request: &PushRequest,
+    ) -> Result<Self> {
+        let group_members = match &request.filter.group {
+            Some(group) => Some(config.group_members(group)?.to_vec()),
+            None => None,
+        };
+
+        let mut skipped = Vec::new();
+        let mut targets = Vec::new();
+        for url in urls {
+            if let Some((remote, reason)) = config.match_disabled(&url) {
+                skipped.push(SkippedRemote {
+                    name: remote.name.clone(),
+                    reason: reason.to_string(),
+                });
+                continue;
+            }
+            let name = config.display_name(&url);
+            if !should_push(&name, &request.filter, group_members.as_deref()) {
+                continue;
+            }
+            let remote = config.match_remote(&url);
+            targets.push(build_target(url, name, remote, config, defaults, request));
+        }
+
+        Ok(PushEngine {
+            branch: branch.to_string(),
+            targets,
+            skipped,
+        })
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    pub fn targets(&self) -> &[PushTarget] {
+        &self.targets
+    }
+
+    pub fn skipped(&self) -> &[SkippedRemote] {
+        &self.skipped
+    }
+
+    /// 执行推送：首轮推送全部目标，之后只重试仍有重试次数的失败目标
+    pub fn run(&self, git: &dyn GitBackend, observer: &dyn PushObserver) -> PushReport {
+        // 尚未成功的目标记为失败，attempts 为 0 表示还没有尝试过
+        let mut outcomes: Vec<PushOutcome> = self
+            .targets
+            .iter()
+            .map(|target| PushOutcome {
+                name: target.name.clone(),
+                url: target.url.clone(),
+                status: if target.refused.is_some() {
+                    PushStatus::Refused
+                } else {
+                    PushStatus::Failed
+                },
+                attempts: 0,
+                error: target.refused.clone(),
+            })
+            .collect();
+
+        let mut round = 0u32;
+        loop {
+            let pending: Vec<usize> = outcomes
+                .iter()
+                .enumerate()
+                .filter(|(i, o)| {
+                    o.status == PushStatus::Failed
+                        && o.attempts <= self.targets[*i].retry.max_retries
+                })
+                .map(|(i, _)| i)
+                .collect();
+            if pending.is_empty() {
+                break; // 所有任务成功或已达到各自的最大重试次数
+            }
+
+            observer.on_event(PushEvent::RoundStarted {
+                round,
+                pending: &pending,
+            });
+            let results: Vec<(usize, AttemptEnd)> = pending
+                .par_iter()
+                .map(|&index| (index, self.attempt(git, index, round, observer)))
+                .collect();
+            observer.on_event(PushEvent::RoundFinished { round });
+
+            for (index, end) in results {
+                let outcome = &mut outcomes[index];
+                outcome.attempts += 1;
+                outcome.error = end.error();
+                if outcome.error.is_none() {
+                    outcome.status = PushStatus::Success;
+                }
+            }
+            round += 1;
+        }
+
+        PushReport { outcomes }
+    }
+
+    /// 对单个目标执行一次（可能带连接验证的）推送
+    fn attempt(
+        &self,
+        git: &dyn GitBackend,
+        index: usize,
+        round: u32,
+        observer: &dyn PushObserver,
+    ) -> AttemptEnd {
+        let target = &self.targets[index];
+
+        // 重试前按该仓库的重试间隔等待
+        if round > 0 && target.retry.delay_ms > 0 {
+            observer.on_event(PushEvent::Waiting {
+                index,
+                delay_ms: target.retry.delay_ms,
+            });
+            thread::sleep(Duration::from_millis(target.retry.delay_ms));
+        }
+
+        let end = self.check_and_push(git, index, observer);
+        observer.on_event(PushEvent::Finished { index, end: &end });
+        end
+    }
+
+    fn check_and_push(
+        &self,
+        git: &dyn GitBackend,
+        index: usize,
+        observer: &dyn PushObserver,
+    ) -> AttemptEnd {
+        let target = &self.targets[index];
+        if !target.skip_check {
+            observer.on_event(PushEvent::Checking { index });
+            match git.is_reachable(&target.url, target.retry.timeout_secs, &target.git) {
+                Ok(true) => {}
+                Ok(false) => return AttemptEnd::Unreachable,
+                Err(e) => return AttemptEnd::CheckFailed(e.to_string()),
+            }
+        }
+
+        observer.on_event(PushEvent::Pushing { index });
+        match git.push(
+            &target.url,
+            &self.branch,
+            &target.options,
+            target.retry.timeout_secs,
+            &target.git,
+        ) {
+            Ok(()) => AttemptEnd::Pushed,
+            Err(e) => AttemptEnd::PushFailed(e.to_string()),
+        }
+    }
+}
+
+/// 解析单个仓库的推送策略：命令行参数 > 远程仓库配置 > 各层默认配置
+fn build_target(
+    url: String,
+    name: String,
+    remote: Option<&Remote>,
+    config: &Config,
+    defaults: &ResolvedDefaults,
+    request: &PushRequest,
+) -> PushTarget {
+    let retry_args = &request.retry;
+    let retry = RetryConfig {
+        max_retries: retry_args
+            .retry
+            .or(remote.and_then(|r| r.retry))
+            .unwrap_or(defaults.retry),
+        delay_ms: retry_args
+            .retry_delay
+            .or(remote.and_then(|r| r.retry_delay))
+            .unwrap_or(defaults.retry_delay),
+        timeout_secs: retry_args
+            .timeout
+            .or(remote.and_then(|r| r.timeout))
+            .unwrap_or(defaults.timeout),
+    };
+
+    let mut options = request.options.clone();
+    if let Some(remote) = remote {
+        options.extra_args.extend(remote.extra_args.clone());
+    }
+
+    // 受保护的仓库拒绝任何形式的强制推送
+    let refused = (remote.is_some_and(|r| r.allow_force == Some(false)) && wants_force(&options))
+        .then(|| "受保护的远程仓库（allow_force = false），拒绝强制推送".to_string());
+
+    PushTarget {
+        url,
+        name,
+        retry,
+        skip_check: request.skip_check || remote.and_then(|r| r.skip_check).unwrap_or(false),
+        options,
+        // ssh 与代理参数使用叠加后的默认配置
+        git: remote
+            .map(|r| r.git_overrides(&config.defaults))
+            .unwrap_or_default(),
+        refused,
+    }
+}
+
+/// 判断推送选项是否包含强制推送
+fn wants_force(options: &PushOptions) -> bool {
+    options.force
+        || options.force_with_lease
+        || options.extra_args.iter().any(|arg| {
+            arg == "-f"
+                || arg == "--force"
+                || arg.starts_with("--force-with-lease")
+                || arg.starts_with('+')
+        })
+}
+
+/// 判断是否应该推送到该仓库
+fn should_push(name: &str, filter: &PushFilter, group_members: Option<&[String]>) -> bool {
+    let PushFilter { only, except, .. } = filter;
+
+    // 分组过滤
+    if let Some(members) = group_members {
+        if !members.iter().any(|member| member == name) {
+            return false;
+        }
+    }
+
+    // only 过滤
+    if !only.is_empty() && (name == UNNAMED_REMOTE || !only.contains(&name.to_string())) {
+        return false;
+    }
+
+    // except 过滤
+    if !except.is_empty() && name != UNNAMED_REMOTE && except.contains(&name.to_string()) {
+        return false;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DisableReason, DisabledRemote};
+    use crate::git::fixtures::{self, remote, A, B};
+    use crate::git::{FakeBehavior, FakeGit};
+
+    /// b 是受保护的仓库
+    fn config() -> Config {
+        let mut config = fixtures::config();
+        config.remotes[1].allow_force = Some(false);
+        config
+    }
+
+    fn defaults(retry: u32) -> ResolvedDefaults {
+        ResolvedDefaults {
+            retry,
+            retry_delay: 0,
+            timeout: 5,
+            check_timeout: 5,
+            alias: "pb".to_string(),
+            group: None,
+        }
+    }
+
+    fn run(git: &FakeGit, retry: u32, request: &PushRequest) -> PushReport {
+        let urls = vec![A.to_string(), B.to_string()];
+        PushEngine::plan(&config(), &defaults(retry), urls, "main", request)
+            .unwrap()
+            .run(git, &())
+    }
+
+    fn outcome<'a>(report: &'a PushReport, name: &str) -> &'a PushOutcome {
+        report.outcomes.iter().find(|o| o.name == name).unwrap()
+    }
+
+    #[test]
+    fn pushes_to_every_target() {
+        let git = FakeGit::new()
+            .with_server(A, FakeBehavior::Ok)
+            .with_server(B, FakeBehavior::Ok);
+        let report = run(&git, 0, &PushRequest::default());
+
+        assert!(report.is_success());
+        assert_eq!(git.server_ref(A, "main").as_deref(), Some("c1"));
+        assert_eq!(git.server_ref(B, "main").as_deref(), Some("c1"));
+    }
+
+    #[test]
+    fn retries_flaky_push_until_it_succeeds() {
+        let git = FakeGit::new()
+            .with_server(A, FakeBehavior::FlakyPush(2))
+            .with_server(B, FakeBehavior::Ok);
+        let report = run(&git, 2, &PushRequest::default());
+
+        assert!(report.is_success());
+        assert_eq!(outcome(&report, "a").attempts, 3);
+        assert_eq!(outcome(&report, "b").attempts, 1);
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let git = FakeGit::new()
+            .with_server(A, FakeBehavior::FlakyPush(5))
+            .with_server(B, FakeBehavior::Ok);
+        let report = run(&git, 1, &PushRequest::default());
+
+        let a = outcome(&report, "a");
+        assert_eq!(a.status, PushStatus::Failed);
+        assert_eq!(a.attempts, 2);
+        assert!(a.error.as_deref().unwrap().contains("Connection reset"));
+    }
+
+    #[test]
+    fn auth_failure_is_reported_as_unreachable() {
+        let git = FakeGit::new()
+            .with_server(A, FakeBehavior::AuthFailure)
+            .with_server(B, FakeBehavior::Ok);
+        let report = run(&git, 0, &PushRequest::default());
+
+        let a = outcome(&report, "a");
+        assert_eq!(a.status, PushStatus::Failed);
+        assert_eq!(a.error.as_deref(), Some("远程仓库无法访问"));
+        assert!(!git.calls().contains(&format!("push {} main", A)));
+    }
+
+    #[test]
+    fn skip_check_surfaces_push_error() {
+        let git = FakeGit::new()
+            .with_server(A, FakeBehavior::AuthFailure)
+            .with_server(B, FakeBehavior::Ok);
+        let request = PushRequest {
+            skip_check: true,
+            ..Default::default()
+        };
+        let report = run(&git, 0, &request);
+
+        let error = outcome(&report, "a").error.clone().unwrap();
+        assert!(error.contains("Authentication failed"), "{}", error);
+    }
+
+    #[test]
+    fn zero_remote_timeout_does_not_limit_the_check() {
+        let git = FakeGit::new()
+            .with_server(A, FakeBehavior::Slow(30))
+            .with_server(B, FakeBehavior::Slow(30));
+        let mut config = config();
+        config.remotes[0].timeout = Some(0);
+        let urls = vec![A.to_string(), B.to_string()];
+        let report = PushEngine::plan(&config, &defaults(0), urls, "main", &PushRequest::default())
+            .unwrap()
+            .run(&git, &());
+
+        // a 设置了 timeout = 0，检查与推送都不限制时间；b 使用默认的 5 秒
+        assert_eq!(outcome(&report, "a").status, PushStatus::Success);
+        let error = outcome(&report, "b").error.clone().unwrap();
+        assert!(error.contains("检查超时（5秒）"), "{}", error);
+    }
+
+    #[test]
+    fn timeout_fails_the_check() {
+        let git = FakeGit::new()
+            .with_server(A, FakeBehavior::Timeout)
+            .with_server(B, FakeBehavior::Ok);
+        let report = run(&git, 0, &PushRequest::default());
+
+        let error = outcome(&report, "a").error.clone().unwrap();
+        assert!(error.contains("检查超时（5秒）"), "{}", error);
+        assert_eq!(outcome(&report, "b").status, PushStatus::Success);
+    }
+
+    #[test]
+    fn diverged_remote_rejects_plain_push() {
+        let diverged = FakeBehavior::Diverged {
+            ahead: 1,
+            behind: 2,
+        };
+        let git = FakeGit::new()
+            .with_server(A, diverged)
+            .with_server(B, FakeBehavior::Ok);
+        let report = run(&git, 0, &PushRequest::default());
+
+        let error = outcome(&report, "a").error.clone().unwrap();
+        assert!(error.contains("non-fast-forward"), "{}", error);
+        assert_eq!(git.server_ref(A, "main"), None);
+    }
+
+    #[test]
+    fn disabled_shorter_base_does_not_shadow_enabled_remote() {
+        let org = "https://github.com/org/demo.git";
+        let other = "https://github.com/me/demo.git";
+        let mut config = config();
+        config
+            .remotes
+            .push(remote("org", "https://github.com/org/"));
+        config.disabled.push(DisabledRemote {
+            remote: Remote {
+                enabled: false,
+                ..remote("github", "https://github.com/")
+            },
+            reason: DisableReason::Disabled,
+        });
+        let urls = vec![org.to_string(), other.to_string()];
+        let engine =
+            PushEngine::plan(&config, &defaults(0), urls, "main", &PushRequest::default()).unwrap();
+
+        let names: Vec<&str> = engine.targets().iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["org"]);
+        let skipped: Vec<&str> = engine.skipped().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(skipped, vec!["github"]);
+    }
+
+    #[test]
+    fn force_push_is_refused_for_protected_remote() {
+        let diverged = FakeBehavior::Diverged {
+            ahead: 1,
+            behind: 2,
+        };
+        let git = FakeGit::new()
+            .with_server(A, diverged)
+            .with_server(B, FakeBehavior::Ok);
+        let request = PushRequest {
+            options: PushOptions {
+                force: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let report = run(&git, 0, &request);
+
+        assert_eq!(outcome(&report, "a").status, PushStatus::Success);
+        let b = outcome(&report, "b");
+        assert_eq!(b.status, PushStatus::Refused);
+        assert_eq!(b.attempts, 0);
+        assert_eq!(git.pushes(), vec![(A.to_string(), "main".to_string())]);
+    }
+}
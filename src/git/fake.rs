@@ -0,0 +1,591 @@
+//! 内存中的 [`GitBackend`] 实现，用于测试命令与推送逻辑
+//!
+//! 本地仓库与远程服务器都只存在于内存中，远程服务器按地址注册，
+//! 可以模拟超时、认证失败、偶发推送失败与分支分叉：
+//!
+//! ```
+//! use yewpb::git::{FakeBehavior, FakeGit, GitBackend, GitOverrides, PushOptions};
+//!
+//! let git = FakeGit::new()
+//!     .with_server("https://example.com/a.git", FakeBehavior::Ok)
+//!     .with_server("https://example.com/b.git", FakeBehavior::AuthFailure);
+//! let overrides = GitOverrides::default();
+//!
+//! git.push("https://example.com/a.git", "main", &PushOptions::default(), 0, &overrides)?;
+//! assert!(git
+//!     .push("https://example.com/b.git", "main", &PushOptions::default(), 0, &overrides)
+//!     .is_err());
+//! assert_eq!(git.pushes(), vec![("https://example.com/a.git".to_string(), "main".to_string())]);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use super::{config_unset_args, format_args, push_url_key, GitBackend, GitOverrides, PushOptions};
+use anyhow::{bail, Result};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// 模拟的远程服务器行为
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FakeBehavior {
+    /// 正常访问
+    Ok,
+    /// 检查与推送都超时
+    Timeout,
+    /// 认证失败，检查返回不可访问，推送报错
+    AuthFailure,
+    /// 前 n 次推送失败，之后恢复正常
+    FlakyPush(u32),
+    /// 每次检查与推送需要 n 秒，超时设置不足 n 秒时超时，超时为 0 时不限制
+    Slow(u64),
+    /// 远程分支与本地分叉，非强制推送被拒绝
+    Diverged { ahead: usize, behind: usize },
+}
+
+/// 模拟的远程服务器
+#[derive(Debug, Clone)]
+struct FakeServer {
+    behavior: FakeBehavior,
+    /// 分支名 -> commit
+    refs: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+struct State {
+    available: bool,
+    git_dir: Option<PathBuf>,
+    /// None 表示游离 HEAD
+    branch: Option<String>,
+    /// 本地提交历史，最后一个为 HEAD
+    commits: Vec<String>,
+    /// 其他目录中的仓库及其远程地址
+    repos: HashMap<PathBuf, Vec<(String, String)>>,
+    servers: HashMap<String, FakeServer>,
+    config: BTreeMap<String, String>,
+    /// 当前仓库的配置，按配置文件中的顺序排列，远程仓库也保存在其中
+    repo_config: Vec<(String, String)>,
+    pushes: Vec<(String, String)>,
+    calls: Vec<String>,
+}
+
+/// 内存中的 git 仓库与远程服务器
+///
+/// 未注册的地址视为无法访问；所有调用都会记录在 [`FakeGit::calls`] 中
+#[derive(Debug)]
+pub struct FakeGit {
+    state: Mutex<State>,
+}
+
+impl Default for FakeGit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeGit {
+    /// 位于 main 分支、只有一个提交、没有远程仓库的空仓库
+    pub fn new() -> Self {
+        FakeGit {
+            state: Mutex::new(State {
+                available: true,
+                git_dir: Some(PathBuf::from(".git")),
+                branch: Some("main".to_string()),
+                commits: vec!["c1".to_string()],
+                repos: HashMap::new(),
+                servers: HashMap::new(),
+                config: BTreeMap::new(),
+                repo_config: Vec::new(),
+                pushes: Vec::new(),
+                calls: Vec::new(),
+            }),
+        }
+    }
+
+    /// 模拟未安装 git
+    pub fn without_git(self) -> Self {
+        self.lock().available = false;
+        self
+    }
+
+    /// 模拟当前目录不是 git 仓库
+    pub fn outside_repo(self) -> Self {
+        self.lock().git_dir = None;
+        self
+    }
+
+    /// 指定 .git 目录，hook 相关操作会在其中读写文件
+    pub fn with_git_dir(self, dir: impl Into<PathBuf>) -> Self {
+        self.lock().git_dir = Some(dir.into());
+        self
+    }
+
+    /// 切换当前分支
+    pub fn with_branch(self, branch: &str) -> Self {
+        self.lock().branch = Some(branch.to_string());
+        self
+    }
+
+    /// 进入游离 HEAD 状态
+    pub fn detached(self) -> Self {
+        self.lock().branch = None;
+        self
+    }
+
+    /// 添加已存在的远程仓库
+    pub fn with_remote(self, name: &str, url: &str) -> Self {
+        self.lock().add_entry(&format!("remote.{}.url", name), url);
+        self
+    }
+
+    /// 为已存在的远程仓库追加推送地址
+    pub fn with_push_url(self, name: &str, url: &str) -> Self {
+        {
+            let mut state = self.lock();
+            if state.has_remote(name) {
+                state.add_entry(&push_url_key(name), url);
+            }
+        }
+        self
+    }
+
+    /// 注册其他目录中的仓库及其远程地址，供 `remote_urls_in` 使用
+    pub fn with_repo(self, dir: impl Into<PathBuf>, remotes: &[(&str, &str)]) -> Self {
+        let remotes = remotes
+            .iter()
+            .map(|(name, url)| (name.to_string(), url.to_string()))
+            .collect();
+        self.lock().repos.insert(dir.into(), remotes);
+        self
+    }
+
+    /// 注册远程服务器
+    pub fn with_server(self, url: &str, behavior: FakeBehavior) -> Self {
+        self.lock().servers.insert(
+            url.to_string(),
+            FakeServer {
+                behavior,
+                refs: HashMap::new(),
+            },
+        );
+        self
+    }
+
+    /// 预置全局 git 配置
+    pub fn with_config(self, key: &str, value: &str) -> Self {
+        self.lock()
+            .config
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// 为当前仓库配置追加一个值
+    pub fn with_repo_config(self, key: &str, value: &str) -> Self {
+        self.lock().add_entry(key, value);
+        self
+    }
+
+    /// 在本地新增一个提交
+    pub fn commit(&self, id: &str) {
+        self.lock().commits.push(id.to_string());
+    }
+
+    /// 修改已注册服务器的行为
+    pub fn set_behavior(&self, url: &str, behavior: FakeBehavior) {
+        if let Some(server) = self.lock().servers.get_mut(url) {
+            server.behavior = behavior;
+        }
+    }
+
+    /// 远程仓库的 (拉取地址, 推送地址)
+    pub fn remote(&self, name: &str) -> Option<(String, Vec<String>)> {
+        let state = self.lock();
+        if !state.has_remote(name) {
+            return None;
+        }
+        let url = state
+            .values(&format!("remote.{}.url", name))
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        Some((url, state.values(&push_url_key(name))))
+    }
+
+    /// 服务器上指定分支的 commit
+    pub fn server_ref(&self, url: &str, branch: &str) -> Option<String> {
+        self.lock()
+            .servers
+            .get(url)
+            .and_then(|server| server.refs.get(branch).cloned())
+    }
+
+    /// 成功的推送记录 (地址, 分支)，按完成顺序排列
+    pub fn pushes(&self) -> Vec<(String, String)> {
+        self.lock().pushes.clone()
+    }
+
+    /// 全局 git 配置
+    pub fn config(&self, key: &str) -> Option<String> {
+        self.lock().config.get(key).cloned()
+    }
+
+    /// 当前仓库配置中某一项的所有值
+    pub fn repo_config(&self, key: &str) -> Vec<String> {
+        self.lock().values(key)
+    }
+
+    /// 所有调用的记录，形如 `push https://example.com/a.git main`
+    pub fn calls(&self) -> Vec<String> {
+        self.lock().calls.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // 测试线程 panic 后仍然允许检查状态
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, call: String) -> MutexGuard<'_, State> {
+        let mut state = self.lock();
+        state.calls.push(call);
+        state
+    }
+}
+
+impl State {
+    /// 配置项的所有值，按文件中的顺序排列
+    fn values(&self, key: &str) -> Vec<String> {
+        self.repo_config
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .collect()
+    }
+
+    /// 与 git config --add 一致：追加到同一小节的最后，没有该小节时追加到文件末尾
+    fn add_entry(&mut self, key: &str, value: &str) {
+        let section = section_of(key);
+        let index = self
+            .repo_config
+            .iter()
+            .rposition(|(k, _)| section_of(k) == section)
+            .map_or(self.repo_config.len(), |i| i + 1);
+        self.repo_config
+            .insert(index, (key.to_string(), value.to_string()));
+    }
+
+    /// 删除小节中的所有配置项，返回是否删除了内容
+    fn remove_section(&mut self, section: &str) -> bool {
+        let before = self.repo_config.len();
+        self.repo_config.retain(|(k, _)| section_of(k) != section);
+        self.repo_config.len() != before
+    }
+
+    fn has_remote(&self, name: &str) -> bool {
+        self.remote_names().contains(name)
+    }
+
+    /// 与 git 一致：存在任何 `remote.<name>.*` 配置即视为存在该远程仓库
+    fn remote_names(&self) -> HashSet<String> {
+        self.repo_config
+            .iter()
+            .filter_map(|(key, _)| remote_key(key).map(|(name, _)| name.to_string()))
+            .collect()
+    }
+}
+
+impl GitBackend for FakeGit {
+    fn check_available(&self) -> Result<()> {
+        if !self.lock().available {
+            bail!("Git 命令不可用，请确保已安装 Git 并添加到 PATH 环境变量");
+        }
+        Ok(())
+    }
+
+    fn ensure_repo(&self) -> Result<()> {
+        if self.lock().git_dir.is_none() {
+            bail!("当前目录不是 git 仓库。");
+        }
+        Ok(())
+    }
+
+    fn git_dir(&self) -> Result<PathBuf> {
+        match &self.lock().git_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => bail!("git 命令执行失败: fatal: not a git repository"),
+        }
+    }
+
+    fn current_branch(&self) -> Result<String> {
+        match &self.lock().branch {
+            Some(branch) => Ok(branch.clone()),
+            None => bail!("当前处于游离 HEAD 状态，请先切换到分支再推送。"),
+        }
+    }
+
+    fn remote_names(&self) -> Result<HashSet<String>> {
+        Ok(self.lock().remote_names())
+    }
+
+    fn remote_url(&self, name: &str) -> Result<String> {
+        match self.remote(name) {
+            Some((url, _)) => Ok(url),
+            None => bail!("git 命令执行失败: error: No such remote '{}'", name),
+        }
+    }
+
+    fn remote_push_urls(&self, name: &str) -> Result<Vec<String>> {
+        // 与 git 一致：没有单独配置 pushurl 时使用拉取地址
+        match self.remote(name) {
+            Some((url, push_urls)) if push_urls.is_empty() => Ok(vec![url]),
+            Some((_, push_urls)) => Ok(push_urls),
+            None => bail!("git 命令执行失败: error: No such remote '{}'", name),
+        }
+    }
+
+    fn remote_explicit_push_urls(&self, name: &str) -> Result<Vec<String>> {
+        Ok(self.remote(name).map(|(_, urls)| urls).unwrap_or_default())
+    }
+
+    fn remote_urls_in(&self, dir: &Path) -> Result<Vec<(String, String)>> {
+        match self.lock().repos.get(dir) {
+            Some(remotes) => Ok(remotes.clone()),
+            None => bail!("'{}' 不是 git 仓库", dir.display()),
+        }
+    }
+
+    fn add_remote(&self, name: &str, url: &str) -> Result<()> {
+        let mut state = self.record(format!("remote add {} {}", name, url));
+        if state.has_remote(name) {
+            bail!("git 命令执行失败: error: remote {} already exists.", name);
+        }
+        state.add_entry(&format!("remote.{}.url", name), url);
+        Ok(())
+    }
+
+    fn remove_remote(&self, name: &str) -> Result<()> {
+        let mut state = self.record(format!("remote remove {}", name));
+        if !state.remove_section(&format!("remote.{}", name)) {
+            bail!("git 命令执行失败: error: No such remote: '{}'", name);
+        }
+        Ok(())
+    }
+
+    fn set_remote_url(&self, name: &str, url: &str) -> Result<()> {
+        let mut state = self.record(format!("remote set-url {} {}", name, url));
+        if !state.has_remote(name) {
+            bail!("git 命令执行失败: error: No such remote '{}'", name);
+        }
+        // 替换第一个拉取地址，没有时新增
+        let key = format!("remote.{}.url", name);
+        match state.repo_config.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, value)) => *value = url.to_string(),
+            None => state.add_entry(&key, url),
+        }
+        Ok(())
+    }
+
+    fn remove_push_url(&self, name: &str, url: &str) -> Result<()> {
+        // 与 ProcessGit 相同，通过 git config 按原文删除
+        let key = push_url_key(name);
+        let mut state = self.record(format_args(&config_unset_args(&key, Some(url))));
+        if !state.has_remote(name) {
+            bail!("git 命令执行失败: error: No such remote '{}'", name);
+        }
+        state.repo_config.retain(|(k, v)| *k != key || v != url);
+        Ok(())
+    }
+
+    fn add_push_url(&self, name: &str, url: &str) -> Result<()> {
+        let mut state = self.record(format!("remote set-url --add --push {} {}", name, url));
+        if !state.has_remote(name) {
+            bail!("git 命令执行失败: error: No such remote '{}'", name);
+        }
+        state.add_entry(&push_url_key(name), url);
+        Ok(())
+    }
+
+    fn is_reachable(
+        &self,
+        url: &str,
+        timeout_secs: u64,
+        _overrides: &GitOverrides,
+    ) -> Result<bool> {
+        let state = self.record(format!("ls-remote {}", url));
+        match state.servers.get(url).map(|server| &server.behavior) {
+            None | Some(FakeBehavior::AuthFailure) => Ok(false),
+            Some(FakeBehavior::Timeout) => bail!("检查超时（{}秒）", timeout_secs),
+            Some(FakeBehavior::Slow(secs)) if timed_out(*secs, timeout_secs) => {
+                bail!("检查超时（{}秒）", timeout_secs)
+            }
+            Some(_) => Ok(true),
+        }
+    }
+
+    fn ls_remote_ref(
+        &self,
+        url: &str,
+        branch: &str,
+        _overrides: &GitOverrides,
+    ) -> Result<Option<String>> {
+        let state = self.record(format!("ls-remote {} refs/heads/{}", url, branch));
+        let Some(server) = state.servers.get(url) else {
+            return Ok(None);
+        };
+        match &server.behavior {
+            FakeBehavior::AuthFailure => Ok(None),
+            FakeBehavior::Timeout => bail!("无法获取远程仓库 '{}' 的引用: 连接超时", url),
+            FakeBehavior::Diverged { .. } => Ok(Some(diverged_commit(url))),
+            _ => Ok(server.refs.get(branch).cloned()),
+        }
+    }
+
+    fn push(
+        &self,
+        url: &str,
+        branch: &str,
+        options: &PushOptions,
+        timeout_secs: u64,
+        _overrides: &GitOverrides,
+    ) -> Result<()> {
+        let mut state = self.record(format!("push {} {}", url, branch));
+        let head = state.commits.last().cloned().unwrap_or_default();
+        let force = options.force || options.force_with_lease;
+        let Some(server) = state.servers.get_mut(url) else {
+            bail!(
+                "git 命令执行失败: fatal: '{}' does not appear to be a git repository",
+                url
+            );
+        };
+        match &mut server.behavior {
+            FakeBehavior::Timeout => bail!("命令超时（{}秒）", timeout_secs),
+            FakeBehavior::Slow(secs) if timed_out(*secs, timeout_secs) => {
+                bail!("命令超时（{}秒）", timeout_secs)
+            }
+            FakeBehavior::AuthFailure => {
+                bail!(
+                    "git 命令执行失败: fatal: Authentication failed for '{}'",
+                    url
+                )
+            }
+            FakeBehavior::FlakyPush(remaining) if *remaining > 0 => {
+                *remaining -= 1;
+                bail!(
+                    "git 命令执行失败: fatal: unable to access '{}': Connection reset by peer",
+                    url
+                )
+            }
+            FakeBehavior::Diverged { .. } if !force => bail!(
+                "git 命令执行失败: ! [rejected] {} -> {} (non-fast-forward)",
+                branch,
+                branch
+            ),
+            _ => {}
+        }
+        // 强制推送后分叉消失
+        if !matches!(server.behavior, FakeBehavior::Slow(_)) {
+            server.behavior = FakeBehavior::Ok;
+        }
+        server.refs.insert(branch.to_string(), head);
+        state.pushes.push((url.to_string(), branch.to_string()));
+        Ok(())
+    }
+
+    fn count_ahead_behind(&self, remote_commit: &str) -> Result<(usize, usize)> {
+        let state = self.record(format!(
+            "rev-list --left-right --count HEAD...{}",
+            remote_commit
+        ));
+        if let Some(index) = state.commits.iter().position(|c| c == remote_commit) {
+            return Ok((state.commits.len() - 1 - index, 0));
+        }
+        let diverged = state
+            .servers
+            .iter()
+            .find_map(|(url, server)| match server.behavior {
+                FakeBehavior::Diverged { ahead, behind }
+                    if diverged_commit(url) == remote_commit =>
+                {
+                    Some((ahead, behind))
+                }
+                _ => None,
+            });
+        match diverged {
+            Some(counts) => Ok(counts),
+            None => bail!(
+                "git 命令执行失败: fatal: bad revision 'HEAD...{}'",
+                remote_commit
+            ),
+        }
+    }
+
+    fn config_get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .record(format!("config --global --get {}", key))
+            .config
+            .get(key)
+            .cloned())
+    }
+
+    fn config_set(&self, key: &str, value: &str) -> Result<()> {
+        self.record(format!("config --global {} {}", key, value))
+            .config
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn config_unset(&self, key: &str) -> Result<()> {
+        self.record(format!("config --global --unset {}", key))
+            .config
+            .remove(key);
+        Ok(())
+    }
+
+    fn repo_config_get_all(&self, key: &str) -> Result<Vec<String>> {
+        Ok(self.repo_config(key))
+    }
+
+    fn repo_config_add(&self, key: &str, value: &str) -> Result<()> {
+        self.record(format!("config --local --add {} {}", key, value))
+            .add_entry(key, value);
+        Ok(())
+    }
+
+    fn repo_config_unset(&self, key: &str, value: Option<&str>) -> Result<()> {
+        let mut state = self.record(format_args(&config_unset_args(key, value)));
+        state
+            .repo_config
+            .retain(|(k, v)| k != key || value.is_some_and(|value| v != value));
+        Ok(())
+    }
+
+    fn repo_config_list(&self) -> Result<Vec<(String, String)>> {
+        Ok(self.lock().repo_config.clone())
+    }
+
+    fn repo_config_remove_section(&self, section: &str) -> Result<()> {
+        let mut state = self.record(format!("config --local --remove-section {}", section));
+        if !state.remove_section(section) {
+            bail!("git 命令执行失败: fatal: no such section: {}", section);
+        }
+        Ok(())
+    }
+}
+
+/// 配置项所在的小节，如 `remote.origin.url` 的小节为 `remote.origin`
+fn section_of(key: &str) -> &str {
+    key.rsplit_once('.').map_or(key, |(section, _)| section)
+}
+
+/// 拆分 `remote.<name>.<var>` 形式的配置项
+fn remote_key(key: &str) -> Option<(&str, &str)> {
+    key.strip_prefix("remote.")?.rsplit_once('.')
+}
+
+/// 耗时 secs 秒的操作在 timeout_secs 的限制下是否超时，0 表示不限制
+fn timed_out(secs: u64, timeout_secs: u64) -> bool {
+    timeout_secs > 0 && secs > timeout_secs
+}
+
+/// 分叉的服务器上分支指向的 commit
+fn diverged_commit(url: &str) -> String {
+    format!("diverged:{}", url)
+}
@@ -0,0 +1,49 @@
+//! 测试共用的远程仓库配置与地址，配合 [`FakeGit`](super::FakeGit) 使用
+//!
+//! [`config`] 中的 a、b 两个远程仓库对 demo 仓库生成的地址分别是 [`A`]、[`B`]，
+//! 各测试在此基础上修改需要的字段：
+//!
+//! ```
+//! use yewpb::git::fixtures::{self, A};
+//!
+//! let mut config = fixtures::config();
+//! config.remotes[1].primary = true;
+//! assert_eq!(config.match_remote(A).unwrap().name, "a");
+//! ```
+
+use crate::config::{save_config, Config, Remote};
+use std::path::{Path, PathBuf};
+
+/// 远程仓库 a 中 demo 仓库的地址
+pub const A: &str = "https://a.example.com/demo.git";
+/// 远程仓库 b 中 demo 仓库的地址
+pub const B: &str = "https://b.example.com/demo.git";
+/// 不属于 [`config`] 中任何远程仓库的地址
+pub const C: &str = "https://c.example.com/demo.git";
+
+/// 只设置名称与 base 的远程仓库
+pub fn remote(name: &str, base: &str) -> Remote {
+    Remote {
+        name: name.to_string(),
+        base: base.to_string(),
+        ..Default::default()
+    }
+}
+
+/// 包含 a、b 两个远程仓库的配置
+pub fn config() -> Config {
+    Config {
+        remotes: vec![
+            remote("a", "https://a.example.com/"),
+            remote("b", "https://b.example.com/"),
+        ],
+        ..Default::default()
+    }
+}
+
+/// 把配置写入 dir/config.toml，返回文件路径
+pub fn write_config(dir: &Path, config: &Config) -> PathBuf {
+    let path = dir.join("config.toml");
+    save_config(&path, config).expect("写入测试配置失败");
+    path
+}
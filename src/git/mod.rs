@@ -0,0 +1,681 @@
+use crate::secret::{self, Credential};
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+pub mod fake;
+pub mod fixtures;
+mod hook;
+
+pub use fake::{FakeBehavior, FakeGit};
+pub use hook::{
+    get_hooks_dir, get_pre_push_hook_path, has_pre_push_hook, install_pre_push_hook,
+    is_push_backup_hook_installed, uninstall_pre_push_hook,
+};
+
+/// 命令模块使用的 git 操作
+///
+/// 正式运行时使用调用 git 进程的 [`ProcessGit`]，测试时使用内存中的 [`FakeGit`]
+pub trait GitBackend: Sync {
+    /// 检查 git 命令是否可用
+    fn check_available(&self) -> Result<()>;
+
+    /// 确认当前目录是可用的 git 仓库
+    fn ensure_repo(&self) -> Result<()>;
+
+    /// 当前仓库的 .git 目录
+    fn git_dir(&self) -> Result<PathBuf>;
+
+    /// 当前分支，游离 HEAD 状态时报错
+    fn current_branch(&self) -> Result<String>;
+
+    /// 当前仓库已有的远程仓库名称集合
+    fn remote_names(&self) -> Result<HashSet<String>>;
+
+    /// 远程仓库的拉取地址
+    fn remote_url(&self, name: &str) -> Result<String>;
+
+    /// 远程仓库的所有推送地址
+    fn remote_push_urls(&self, name: &str) -> Result<Vec<String>>;
+
+    /// 远程仓库中通过 pushurl 显式配置的推送地址，未配置时为空
+    fn remote_explicit_push_urls(&self, name: &str) -> Result<Vec<String>>;
+
+    /// 列出指定仓库中所有远程仓库的地址（包括 pushurl），按 (名称, 地址) 去重
+    fn remote_urls_in(&self, dir: &Path) -> Result<Vec<(String, String)>>;
+
+    /// 添加远程仓库
+    fn add_remote(&self, name: &str, url: &str) -> Result<()>;
+
+    /// 移除远程仓库
+    fn remove_remote(&self, name: &str) -> Result<()>;
+
+    /// 修改远程仓库的拉取地址，保留 fetch refspec 等其他设置
+    fn set_remote_url(&self, name: &str, url: &str) -> Result<()>;
+
+    /// 为远程仓库追加推送地址
+    fn add_push_url(&self, name: &str, url: &str) -> Result<()>;
+
+    /// 删除远程仓库中与 url 完全相同的推送地址
+    fn remove_push_url(&self, name: &str, url: &str) -> Result<()>;
+
+    /// 检查远程仓库是否可访问，超时返回错误；timeout_secs 为 0 表示不限制
+    fn is_reachable(&self, url: &str, timeout_secs: u64, overrides: &GitOverrides) -> Result<bool>;
+
+    /// 通过 ls-remote 获取远程分支的 commit hash，连接失败或分支不存在时返回 None
+    fn ls_remote_ref(
+        &self,
+        url: &str,
+        branch: &str,
+        overrides: &GitOverrides,
+    ) -> Result<Option<String>>;
+
+    /// 推送分支到指定地址，timeout_secs 为 0 表示不限制
+    fn push(
+        &self,
+        url: &str,
+        branch: &str,
+        options: &PushOptions,
+        timeout_secs: u64,
+        overrides: &GitOverrides,
+    ) -> Result<()>;
+
+    /// 通过 rev-list 计算本地 HEAD 与远程 commit 之间的 ahead/behind 数量
+    fn count_ahead_behind(&self, remote_commit: &str) -> Result<(usize, usize)>;
+
+    /// 读取全局 git 配置，不存在时返回 None
+    fn config_get(&self, key: &str) -> Result<Option<String>>;
+
+    /// 设置全局 git 配置（覆盖模式）
+    fn config_set(&self, key: &str, value: &str) -> Result<()>;
+
+    /// 删除全局 git 配置，不存在时视为成功
+    fn config_unset(&self, key: &str) -> Result<()>;
+
+    /// 读取当前仓库配置中某一项的所有值，不存在时为空
+    fn repo_config_get_all(&self, key: &str) -> Result<Vec<String>>;
+
+    /// 为当前仓库配置追加一个值，不覆盖已有的值
+    fn repo_config_add(&self, key: &str, value: &str) -> Result<()>;
+
+    /// 删除当前仓库配置中与 value 完全相同的值，value 为 None 时删除所有值；不存在时视为成功
+    fn repo_config_unset(&self, key: &str, value: Option<&str>) -> Result<()>;
+
+    /// 按文件中的顺序列出当前仓库配置的所有 (key, value)
+    fn repo_config_list(&self) -> Result<Vec<(String, String)>>;
+
+    /// 删除当前仓库配置中的整个小节，如 `remote.origin`
+    fn repo_config_remove_section(&self, section: &str) -> Result<()>;
+}
+
+/// 通过调用 git 进程实现的 [`GitBackend`]
+#[derive(Debug, Clone, Default)]
+pub struct ProcessGit {
+    /// 执行 git 的目录，None 表示当前目录
+    dir: Option<PathBuf>,
+}
+
+impl ProcessGit {
+    /// 在当前目录执行 git
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 在指定的仓库目录中执行 git
+    pub fn in_dir(dir: impl Into<PathBuf>) -> Self {
+        ProcessGit {
+            dir: Some(dir.into()),
+        }
+    }
+
+    fn git(&self) -> Command {
+        let mut cmd = Command::new("git");
+        if let Some(dir) = &self.dir {
+            cmd.current_dir(dir);
+        }
+        cmd
+    }
+
+    /// 相对于执行目录的路径
+    fn path(&self, path: impl AsRef<Path>) -> PathBuf {
+        match &self.dir {
+            Some(dir) => dir.join(path),
+            None => path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl GitBackend for ProcessGit {
+    fn check_available(&self) -> Result<()> {
+        // 检查 git 命令是否可用
+        self.git()
+            .arg("--version")
+            .output()
+            .context("Git 命令不可用，请确保已安装 Git 并添加到 PATH 环境变量")?;
+        Ok(())
+    }
+
+    fn ensure_repo(&self) -> Result<()> {
+        // 确认当前目录是可用的 git 仓库
+        if !self.path(".git").exists() {
+            bail!("当前目录不是 git 仓库。");
+        }
+        run_command(self.git(), &["rev-parse", "--git-dir"])?;
+        Ok(())
+    }
+
+    fn git_dir(&self) -> Result<PathBuf> {
+        // 相对路径基于执行 git 的目录
+        let git_dir = capture_command(self.git(), &["rev-parse", "--git-dir"])?;
+        Ok(self.path(git_dir.trim()))
+    }
+
+    fn current_branch(&self) -> Result<String> {
+        // 获取当前分支，避免在游离 HEAD 状态下误推送
+        let branch = capture_command(self.git(), &["rev-parse", "--abbrev-ref", "HEAD"])?;
+        if branch == "HEAD" {
+            bail!("当前处于游离 HEAD 状态，请先切换到分支再推送。");
+        }
+        Ok(branch)
+    }
+
+    fn remote_names(&self) -> Result<HashSet<String>> {
+        // 获取当前仓库已有的远程仓库名称集合
+        let output = capture_command(self.git(), &["remote"])?;
+        let names = output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect::<HashSet<_>>();
+        Ok(names)
+    }
+
+    fn remote_url(&self, name: &str) -> Result<String> {
+        capture_command(self.git(), &["remote", "get-url", name])
+    }
+
+    fn remote_push_urls(&self, name: &str) -> Result<Vec<String>> {
+        let output = capture_command(self.git(), &["remote", "get-url", "--all", "--push", name])?;
+        Ok(output.lines().map(String::from).collect())
+    }
+
+    fn remote_explicit_push_urls(&self, name: &str) -> Result<Vec<String>> {
+        self.repo_config_get_all(&push_url_key(name))
+    }
+
+    fn remote_urls_in(&self, dir: &Path) -> Result<Vec<(String, String)>> {
+        let dir = dir.to_string_lossy();
+        capture_command(self.git(), &["-C", &dir, "rev-parse", "--git-dir"])
+            .with_context(|| format!("'{}' 不是 git 仓库", dir))?;
+        let output = capture_command(self.git(), &["-C", &dir, "remote", "-v"])?;
+
+        // 输出格式: "name\turl (fetch)" / "name\turl (push)"
+        let mut remotes: Vec<(String, String)> = Vec::new();
+        for line in output.lines() {
+            let mut parts = line.split_whitespace();
+            let (Some(name), Some(url)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            let entry = (name.to_string(), url.to_string());
+            if !remotes.contains(&entry) {
+                remotes.push(entry);
+            }
+        }
+        Ok(remotes)
+    }
+
+    fn add_remote(&self, name: &str, url: &str) -> Result<()> {
+        run_command(self.git(), &["remote", "add", name, url])
+    }
+
+    fn remove_remote(&self, name: &str) -> Result<()> {
+        run_command(self.git(), &["remote", "remove", name])
+    }
+
+    fn set_remote_url(&self, name: &str, url: &str) -> Result<()> {
+        run_command(self.git(), &["remote", "set-url", name, url])
+    }
+
+    fn add_push_url(&self, name: &str, url: &str) -> Result<()> {
+        run_command(
+            self.git(),
+            &["remote", "set-url", "--add", "--push", name, url],
+        )
+    }
+
+    fn remove_push_url(&self, name: &str, url: &str) -> Result<()> {
+        // set-url --delete 按正则匹配，这里需要按原文删除
+        self.repo_config_unset(&push_url_key(name), Some(url))
+    }
+
+    fn is_reachable(&self, url: &str, timeout_secs: u64, overrides: &GitOverrides) -> Result<bool> {
+        // 检查远程仓库是否可访问，带超时控制
+        let resolved = secret::resolve(url)?;
+        let mut child = git_command(self.git(), resolved.credential.as_ref(), overrides)
+            .args(["ls-remote", &resolved.url])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("无法检查远程仓库 '{}' 的可用性", url))?;
+
+        // 与 push 相同，0 表示不限制
+        if timeout_secs == 0 {
+            return Ok(child.wait()?.success());
+        }
+        let timeout = Duration::from_secs(timeout_secs);
+        match child.wait_timeout(timeout)? {
+            Some(status) => Ok(status.success()),
+            None => {
+                // 超时，杀死进程
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!("检查超时（{}秒）", timeout_secs)
+            }
+        }
+    }
+
+    fn ls_remote_ref(
+        &self,
+        url: &str,
+        branch: &str,
+        overrides: &GitOverrides,
+    ) -> Result<Option<String>> {
+        let resolved = secret::resolve(url)?;
+        let output = git_command(self.git(), resolved.credential.as_ref(), overrides)
+            .args([
+                "ls-remote",
+                &resolved.url,
+                &format!("refs/heads/{}", branch),
+            ])
+            .output()
+            .with_context(|| format!("无法获取远程仓库 '{}' 的引用", url))?;
+
+        if !output.status.success() {
+            return Ok(None); // 连接失败或分支不存在
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // 输出格式: "commit_hash\trefs/heads/branch"
+        Ok(stdout.split_whitespace().next().map(String::from))
+    }
+
+    fn push(
+        &self,
+        url: &str,
+        branch: &str,
+        options: &PushOptions,
+        timeout_secs: u64,
+        overrides: &GitOverrides,
+    ) -> Result<()> {
+        // 执行 git push 操作，可选是否配置超时
+        // 构建 git push 命令参数
+        let mut args = vec!["push".to_string()];
+
+        // 专用标志
+        if options.force {
+            args.push("--force".to_string());
+        }
+        if options.force_with_lease {
+            args.push("--force-with-lease".to_string());
+        }
+        if options.set_upstream {
+            args.push("--set-upstream".to_string());
+        }
+        if options.tags {
+            args.push("--tags".to_string());
+        }
+
+        // 远程和分支，地址中的 ${VAR} 在此时才展开
+        let resolved = secret::resolve(url)?;
+        args.push(resolved.url.clone());
+        args.push(branch.to_string());
+
+        // 额外参数（放在最后）
+        args.extend(options.extra_args.clone());
+
+        let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let credential = resolved.credential.as_ref();
+        let result = if timeout_secs > 0 {
+            run_git_with_timeout(
+                git_command(self.git(), credential, overrides),
+                &args_ref,
+                timeout_secs,
+            )
+        } else {
+            run_command(git_command(self.git(), credential, overrides), &args_ref)
+        };
+        // 命令参数与 git 的输出中都可能包含展开后的地址，报错前还原为 ${VAR}
+        result.map_err(|err| anyhow!(resolved.redact(&format!("{:#}", err))))
+    }
+
+    fn count_ahead_behind(&self, remote_commit: &str) -> Result<(usize, usize)> {
+        let output = capture_command(
+            self.git(),
+            &[
+                "rev-list",
+                "--left-right",
+                "--count",
+                &format!("HEAD...{}", remote_commit),
+            ],
+        )?;
+        // 输出格式: "ahead\tbehind"
+        let parts: Vec<&str> = output.split_whitespace().collect();
+        let ahead = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
+        let behind = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+        Ok((ahead, behind))
+    }
+
+    fn config_get(&self, key: &str) -> Result<Option<String>> {
+        let output = self
+            .git()
+            .args(["config", "--global", "--get", key])
+            .output()
+            .context("执行 git config 失败")?;
+
+        if output.status.success() {
+            let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            Ok(Some(value))
+        } else {
+            // 返回码 1 表示 key 不存在，其他错误需要报告
+            if output.status.code() == Some(1) {
+                Ok(None)
+            } else {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                bail!("读取 git 配置 '{}' 失败: {}", key, stderr.trim());
+            }
+        }
+    }
+
+    fn config_set(&self, key: &str, value: &str) -> Result<()> {
+        run_command(self.git(), &["config", "--global", key, value])
+    }
+
+    fn config_unset(&self, key: &str) -> Result<()> {
+        let output = self
+            .git()
+            .args(["config", "--global", "--unset", key])
+            .output()
+            .context("执行 git config 失败")?;
+
+        if output.status.success() || output.status.code() == Some(5) {
+            // code 5 表示 key 不存在，这也是成功
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("删除 git 配置 '{}' 失败: {}", key, stderr.trim());
+        }
+    }
+
+    fn repo_config_get_all(&self, key: &str) -> Result<Vec<String>> {
+        let output = self
+            .git()
+            .args(["config", "--local", "--get-all", key])
+            .output()
+            .context("执行 git config 失败")?;
+        // 返回码 1 表示 key 不存在
+        match output.status.code() {
+            Some(0) => Ok(String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(String::from)
+                .collect()),
+            Some(1) => Ok(Vec::new()),
+            _ => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                bail!("读取 git 配置 '{}' 失败: {}", key, stderr.trim());
+            }
+        }
+    }
+
+    fn repo_config_add(&self, key: &str, value: &str) -> Result<()> {
+        run_command(self.git(), &["config", "--local", "--add", key, value])
+    }
+
+    fn repo_config_unset(&self, key: &str, value: Option<&str>) -> Result<()> {
+        let output = self
+            .git()
+            .args(config_unset_args(key, value))
+            .output()
+            .context("执行 git config 失败")?;
+
+        if output.status.success() || output.status.code() == Some(5) {
+            // code 5 表示 key 不存在，这也是成功
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("删除 git 配置 '{}' 失败: {}", key, stderr.trim());
+        }
+    }
+
+    fn repo_config_list(&self) -> Result<Vec<(String, String)>> {
+        // -z 输出中每项为 "key\nvalue\0"，没有值的布尔项只有 key；值需要原样保留，不能 trim
+        let output = self
+            .git()
+            .args(["config", "--local", "-z", "--list"])
+            .output()
+            .context("执行 git config 失败")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("读取仓库 git 配置失败: {}", stderr.trim());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.split_once('\n') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (entry.to_string(), String::new()),
+            })
+            .collect())
+    }
+
+    fn repo_config_remove_section(&self, section: &str) -> Result<()> {
+        run_command(
+            self.git(),
+            &["config", "--local", "--remove-section", section],
+        )
+    }
+}
+
+/// 单个远程仓库的 git 调用参数，只作用于本次调用，不修改任何 git 配置
+#[derive(Clone, Debug, Default)]
+pub struct GitOverrides {
+    /// 通过 GIT_SSH_COMMAND 指定的 ssh 命令
+    pub ssh_command: Option<String>,
+    /// 通过 git -c 传入的配置项
+    pub config: Vec<(String, String)>,
+}
+
+impl GitOverrides {
+    /// 用于 dry-run 展示的命令前缀，如 `GIT_SSH_COMMAND='ssh -i key' git -c k=v`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(command) = &self.ssh_command {
+            let command =
+                shlex::try_quote(command).map_or_else(|_| command.clone(), |q| q.into_owned());
+            parts.push(format!("GIT_SSH_COMMAND={}", command));
+        }
+        parts.push("git".to_string());
+        for (key, value) in &self.config {
+            let arg = format!("{}={}", key, value);
+            parts.push("-c".to_string());
+            parts.push(shlex::try_quote(&arg).map_or(arg.clone(), |q| q.into_owned()));
+        }
+        parts.join(" ")
+    }
+}
+
+/// 推送选项
+#[derive(Debug, Clone, Default)]
+pub struct PushOptions {
+    pub force: bool,
+    pub force_with_lease: bool,
+    pub set_upstream: bool,
+    pub tags: bool,
+    pub extra_args: Vec<String>,
+}
+
+/// 重试配置
+#[derive(Debug, Clone, Default)]
+pub struct RetryConfig {
+    /// 最大重试次数
+    pub max_retries: u32,
+    /// 重试间隔（毫秒）
+    pub delay_ms: u64,
+    /// 超时时间（秒），0 表示不限制
+    pub timeout_secs: u64,
+}
+
+/// 单次调用专用的 credential helper，从环境变量读取凭据
+const CREDENTIAL_HELPER: &str = "credential.helper=!f() { test \"$1\" = get || exit 0; echo \"username=$YEWPB_GIT_USERNAME\"; echo \"password=$YEWPB_GIT_PASSWORD\"; }; f";
+
+/// 创建 git 命令，附加远程仓库的 ssh 命令与 -c 配置，有凭据时通过 credential helper 传入
+///
+/// 凭据只放在子进程的环境变量中，不会出现在命令参数里；
+/// 先清空已配置的 helper，避免凭据被 store/osxkeychain 等 helper 保存
+fn git_command(
+    mut cmd: Command,
+    credential: Option<&Credential>,
+    overrides: &GitOverrides,
+) -> Command {
+    if let Some(ssh_command) = &overrides.ssh_command {
+        cmd.env("GIT_SSH_COMMAND", ssh_command);
+    }
+    for (key, value) in &overrides.config {
+        cmd.arg("-c").arg(format!("{}={}", key, value));
+    }
+    if let Some(credential) = credential {
+        cmd.args(["-c", "credential.helper=", "-c", CREDENTIAL_HELPER])
+            .env("YEWPB_GIT_USERNAME", &credential.username)
+            .env("YEWPB_GIT_PASSWORD", &credential.password)
+            .env("GIT_TERMINAL_PROMPT", "0");
+    }
+    cmd
+}
+
+/// 远程仓库推送地址的配置项
+pub fn push_url_key(remote: &str) -> String {
+    format!("remote.{}.pushurl", remote)
+}
+
+/// 删除仓库配置项的 git 参数，value 为 None 时删除所有值
+///
+/// --fixed-value 需要 git 2.30，这里用转义后的完整匹配正则兼容旧版本。
+/// ProcessGit 执行、FakeGit 记录和变更预览都使用这里的参数，保证三者一致
+pub fn config_unset_args(key: &str, value: Option<&str>) -> Vec<String> {
+    let mut args = vec![
+        "config".to_string(),
+        "--local".to_string(),
+        "--unset-all".to_string(),
+        key.to_string(),
+    ];
+    if let Some(value) = value {
+        args.push(format!("^{}$", escape_value_regex(value)));
+    }
+    args
+}
+
+/// 把参数拼成可以直接粘贴到 shell 中的命令行，含特殊字符的参数用单引号包裹
+pub fn format_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            let plain = !arg.is_empty()
+                && arg
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./:@=+,%".contains(c));
+            if plain {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 转义 git config 值匹配所用的 POSIX 扩展正则中的特殊字符
+fn escape_value_regex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if ".[]{}()\\*+?^$|".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// 内部函数：执行已构造的 git 命令，不关心输出
+fn run_command(mut cmd: Command, args: &[&str]) -> Result<()> {
+    let output = cmd
+        .args(args)
+        .output()
+        .with_context(|| format!("执行 git 命令失败: {}", args.join(" ")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git 命令执行失败: {}", stderr.trim());
+    }
+    Ok(())
+}
+
+// 内部函数：执行 git 命令，带超时控制
+fn run_git_with_timeout(mut cmd: Command, args: &[&str], timeout_secs: u64) -> Result<()> {
+    let mut child = cmd
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("执行 git 命令失败: {}", args.join(" ")))?;
+
+    let timeout = Duration::from_secs(timeout_secs);
+    match child.wait_timeout(timeout)? {
+        Some(status) => {
+            if !status.success() {
+                let output = child.wait_with_output()?;
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                bail!("git 命令执行失败: {}", stderr.trim());
+            }
+            Ok(())
+        }
+        None => {
+            // 超时，杀死进程
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("命令超时（{}秒）", timeout_secs)
+        }
+    }
+}
+
+// 内部函数：执行已构造的 git 命令并返回输出内容
+fn capture_command(mut cmd: Command, args: &[&str]) -> Result<String> {
+    let output = cmd
+        .args(args)
+        .output()
+        .with_context(|| format!("执行 git 命令失败: {}", args.join(" ")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git 命令执行失败: {}", stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 查找可执行文件的完整路径
+pub fn which_command(cmd: &str) -> Result<Option<String>> {
+    let output = Command::new(if cfg!(windows) { "where" } else { "which" })
+        .arg(cmd)
+        .output()
+        .context("查找命令路径失败")?;
+
+    if output.status.success() {
+        let path = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .map(|s| s.trim().to_string());
+        Ok(path)
+    } else {
+        Ok(None)
+    }
+}
@@ -0,0 +1,345 @@
+use crate::config::Config;
+use crate::git::{config_unset_args, format_args, push_url_key, GitBackend};
+use crate::REMOTE_NAME;
+use anyhow::Result;
+
+/// 记录 yewpb 添加到统一远程仓库的推送地址的仓库配置项
+///
+/// 配置中的 base 修改后旧地址不再匹配任何配置，需要依靠这里的记录区分手动添加的地址
+pub const MANAGED_PUSH_URLS: &str = "yewpb.pushurl";
+
+/// apply 在 git 中创建远程仓库的方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Layout {
+    /// 只创建统一的 yewpb 远程仓库，每个平台一个推送地址
+    #[default]
+    Unified,
+    /// 每个平台一个同名远程仓库，可以单独 fetch
+    Split,
+    /// 同时创建统一的与按平台拆分的远程仓库
+    Both,
+}
+
+impl Layout {
+    /// 是否包含统一的 yewpb 远程仓库
+    pub fn unified(self) -> bool {
+        matches!(self, Layout::Unified | Layout::Both)
+    }
+
+    /// 是否包含按平台拆分的远程仓库
+    pub fn split(self) -> bool {
+        matches!(self, Layout::Split | Layout::Both)
+    }
+}
+
+/// 当前仓库中由 yewpb 创建的远程仓库
+#[derive(Debug, Clone, Default)]
+pub struct ManagedRemotes {
+    /// 统一远程仓库的推送地址，未创建时为 None
+    pub unified: Option<Vec<String>>,
+    /// 按平台拆分的远程仓库 (名称, 地址)，按配置顺序排列
+    pub split: Vec<(String, String)>,
+}
+
+impl ManagedRemotes {
+    /// 读取仓库中的远程仓库
+    ///
+    /// 与配置同名且地址由该配置生成的远程仓库视为拆分布局，禁用的配置也会计入，
+    /// 以便推送时提示跳过
+    pub fn detect(git: &dyn GitBackend, config: &Config) -> Result<Self> {
+        let existing = git.remote_names()?;
+        let unified = if existing.contains(REMOTE_NAME) {
+            Some(git.remote_push_urls(REMOTE_NAME)?)
+        } else {
+            None
+        };
+
+        let mut split = Vec::new();
+        for remote in config
+            .remotes
+            .iter()
+            .chain(config.disabled.iter().map(|d| &d.remote))
+        {
+            if remote.name == REMOTE_NAME || !existing.contains(&remote.name) {
+                continue;
+            }
+            let url = git.remote_url(&remote.name)?;
+            if remote.matches_url(&url) {
+                split.push((remote.name.clone(), url));
+            }
+        }
+        Ok(ManagedRemotes { unified, split })
+    }
+
+    /// 仓库当前使用的布局，没有任何 yewpb 远程仓库时返回 None
+    pub fn layout(&self) -> Option<Layout> {
+        match (self.unified.is_some(), !self.split.is_empty()) {
+            (true, true) => Some(Layout::Both),
+            (true, false) => Some(Layout::Unified),
+            (false, true) => Some(Layout::Split),
+            (false, false) => None,
+        }
+    }
+
+    /// 推送与检查使用的地址：优先使用统一远程仓库的推送地址，否则使用各拆分远程仓库的地址
+    pub fn push_urls(&self) -> Vec<String> {
+        match &self.unified {
+            Some(urls) => urls.clone(),
+            None => self.split.iter().map(|(_, url)| url.clone()).collect(),
+        }
+    }
+}
+
+/// apply 期望的远程仓库状态
+#[derive(Debug, Clone, Default)]
+pub struct DesiredRemotes {
+    pub layout: Layout,
+    /// 统一远程仓库的拉取地址，未指定时使用第一个推送地址
+    pub fetch_url: Option<String>,
+    /// 各平台的 (名称, 地址)，按配置顺序排列
+    pub remotes: Vec<(String, String)>,
+}
+
+/// 对远程仓库的单项变更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteChange {
+    /// 新建远程仓库
+    AddRemote { remote: String, url: String },
+    /// 修改远程仓库的拉取地址
+    SetUrl {
+        remote: String,
+        from: String,
+        to: String,
+    },
+    /// 删除远程仓库
+    RemoveRemote { remote: String },
+    /// 追加推送地址
+    AddPushUrl { remote: String, url: String },
+    /// 删除推送地址
+    RemovePushUrl { remote: String, url: String },
+    /// 保持不变，manual 表示该地址不属于任何配置，是手动添加的
+    Keep {
+        remote: String,
+        url: String,
+        manual: bool,
+    },
+}
+
+impl RemoteChange {
+    /// 是否需要修改仓库
+    pub fn is_change(&self) -> bool {
+        !matches!(self, RemoteChange::Keep { .. })
+    }
+
+    /// 变更后需要检查可用性的地址
+    pub fn new_url(&self) -> Option<&str> {
+        match self {
+            RemoteChange::AddRemote { url, .. } | RemoteChange::AddPushUrl { url, .. } => Some(url),
+            RemoteChange::SetUrl { to, .. } => Some(to),
+            _ => None,
+        }
+    }
+
+    /// 对应的 git 命令，用于展示
+    pub fn command(&self) -> Option<String> {
+        match self {
+            RemoteChange::AddRemote { remote, url } => {
+                Some(format!("git remote add {} {}", remote, url))
+            }
+            RemoteChange::SetUrl { remote, to, .. } => {
+                Some(format!("git remote set-url {} {}", remote, to))
+            }
+            RemoteChange::RemoveRemote { remote } => Some(format!("git remote remove {}", remote)),
+            RemoteChange::AddPushUrl { remote, url } => Some(format!(
+                "git remote set-url --add --push {} {}",
+                remote, url
+            )),
+            RemoteChange::RemovePushUrl { remote, url } => Some(format!(
+                "git {}",
+                format_args(&config_unset_args(&push_url_key(remote), Some(url)))
+            )),
+            RemoteChange::Keep { .. } => None,
+        }
+    }
+
+    /// 执行变更，并同步统一远程仓库推送地址的记录
+    fn apply(&self, git: &dyn GitBackend, tracked: &[String]) -> Result<()> {
+        match self {
+            RemoteChange::AddRemote { remote, url } => git.add_remote(remote, url),
+            RemoteChange::SetUrl { remote, to, .. } => git.set_remote_url(remote, to),
+            RemoteChange::RemoveRemote { remote } => {
+                git.remove_remote(remote)?;
+                if remote == REMOTE_NAME {
+                    git.repo_config_unset(MANAGED_PUSH_URLS, None)?;
+                }
+                Ok(())
+            }
+            RemoteChange::AddPushUrl { remote, url } => {
+                git.add_push_url(remote, url)?;
+                if remote == REMOTE_NAME && !tracked.contains(url) {
+                    git.repo_config_add(MANAGED_PUSH_URLS, url)?;
+                }
+                Ok(())
+            }
+            RemoteChange::RemovePushUrl { remote, url } => {
+                git.remove_push_url(remote, url)?;
+                if remote == REMOTE_NAME {
+                    git.repo_config_unset(MANAGED_PUSH_URLS, Some(url))?;
+                }
+                Ok(())
+            }
+            // 旧版本添加的地址没有记录，保留时补上
+            RemoteChange::Keep {
+                remote,
+                url,
+                manual: false,
+            } if remote == REMOTE_NAME && !tracked.contains(url) => {
+                git.repo_config_add(MANAGED_PUSH_URLS, url)
+            }
+            RemoteChange::Keep { .. } => Ok(()),
+        }
+    }
+}
+
+/// 从仓库当前状态到期望状态的变更计划
+#[derive(Debug, Clone, Default)]
+pub struct RemotePlan {
+    pub changes: Vec<RemoteChange>,
+}
+
+impl RemotePlan {
+    /// 比较仓库当前的远程仓库与期望状态
+    ///
+    /// yewpb 添加过的推送地址（记录在 [`MANAGED_PUSH_URLS`] 中，或由 `known` 中的配置生成，
+    /// `known` 包括禁用与分组外的远程仓库）不再需要时删除；其他地址视为手动添加的，
+    /// 保留不动，`prune` 为 true 时一并删除
+    pub fn compute(
+        git: &dyn GitBackend,
+        known: &Config,
+        desired: &DesiredRemotes,
+        prune: bool,
+    ) -> Result<Self> {
+        let current = ManagedRemotes::detect(git, known)?;
+        let existing = git.remote_names()?;
+        let mut changes = Vec::new();
+
+        // 统一远程仓库：保留已有的远程仓库，只调整地址
+        let urls: Vec<&String> = desired.remotes.iter().map(|(_, url)| url).collect();
+        if desired.layout.unified() && !urls.is_empty() {
+            let fetch_url = desired.fetch_url.as_ref().unwrap_or(urls[0]);
+            let mut kept = Vec::new();
+            if existing.contains(REMOTE_NAME) {
+                let current_fetch = git.remote_url(REMOTE_NAME)?;
+                if &current_fetch != fetch_url {
+                    changes.push(RemoteChange::SetUrl {
+                        remote: REMOTE_NAME.to_string(),
+                        from: current_fetch,
+                        to: fetch_url.clone(),
+                    });
+                }
+                let tracked = git.repo_config_get_all(MANAGED_PUSH_URLS)?;
+                for url in git.remote_explicit_push_urls(REMOTE_NAME)? {
+                    let owned = tracked.contains(&url) || known.match_url(&url).is_some();
+                    if urls.contains(&&url) {
+                        kept.push(url.clone());
+                        changes.push(RemoteChange::Keep {
+                            remote: REMOTE_NAME.to_string(),
+                            url,
+                            manual: false,
+                        });
+                    } else if owned || prune {
+                        changes.push(RemoteChange::RemovePushUrl {
+                            remote: REMOTE_NAME.to_string(),
+                            url,
+                        });
+                    } else {
+                        changes.push(RemoteChange::Keep {
+                            remote: REMOTE_NAME.to_string(),
+                            url,
+                            manual: true,
+                        });
+                    }
+                }
+            } else {
+                changes.push(RemoteChange::AddRemote {
+                    remote: REMOTE_NAME.to_string(),
+                    url: fetch_url.clone(),
+                });
+            }
+            for url in urls.iter().filter(|url| !kept.contains(url)) {
+                changes.push(RemoteChange::AddPushUrl {
+                    remote: REMOTE_NAME.to_string(),
+                    url: url.to_string(),
+                });
+            }
+        } else if existing.contains(REMOTE_NAME) {
+            changes.push(RemoteChange::RemoveRemote {
+                remote: REMOTE_NAME.to_string(),
+            });
+        }
+
+        // 按平台拆分的远程仓库：同名远程仓库归配置所有，地址不同时原地修改
+        let split: Vec<&(String, String)> = desired
+            .remotes
+            .iter()
+            .filter(|(name, _)| desired.layout.split() && name != REMOTE_NAME)
+            .collect();
+        for (name, url) in &split {
+            if !existing.contains(name) {
+                changes.push(RemoteChange::AddRemote {
+                    remote: name.clone(),
+                    url: url.clone(),
+                });
+                continue;
+            }
+            let current_url = git.remote_url(name)?;
+            if &current_url == url {
+                changes.push(RemoteChange::Keep {
+                    remote: name.clone(),
+                    url: url.clone(),
+                    manual: false,
+                });
+            } else {
+                changes.push(RemoteChange::SetUrl {
+                    remote: name.clone(),
+                    from: current_url,
+                    to: url.clone(),
+                });
+            }
+        }
+        for (name, _) in &current.split {
+            if !split.iter().any(|(wanted, _)| wanted == name) {
+                changes.push(RemoteChange::RemoveRemote {
+                    remote: name.clone(),
+                });
+            }
+        }
+
+        Ok(RemotePlan { changes })
+    }
+
+    /// 仓库已是期望状态
+    pub fn is_noop(&self) -> bool {
+        !self.changes.iter().any(RemoteChange::is_change)
+    }
+
+    /// 按顺序执行所有变更
+    pub fn apply(&self, git: &dyn GitBackend) -> Result<()> {
+        let tracked = git.repo_config_get_all(MANAGED_PUSH_URLS)?;
+        for change in &self.changes {
+            change.apply(git, &tracked)?;
+        }
+        Ok(())
+    }
+
+    /// 新增或修改的地址，去重后按计划顺序排列
+    pub fn new_urls(&self) -> Vec<&str> {
+        let mut urls: Vec<&str> = Vec::new();
+        for url in self.changes.iter().filter_map(RemoteChange::new_url) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+}
@@ -0,0 +1,49 @@
+//! yewpb 的核心库：配置加载、远程地址生成与多仓库推送
+//!
+//! `yewpb` 命令行工具只是这里的一个前端，其他工具可以直接复用配置解析与推送逻辑：
+//!
+//! ```no_run
+//! use yewpb::config::config_path;
+//! use yewpb::git::{GitBackend, ProcessGit};
+//! use yewpb::{load_layered_config, Defaults, PushEngine, PushRequest, REMOTE_NAME};
+//!
+//! let layered = load_layered_config(&config_path()?)?;
+//! let defaults = layered.resolve(&Defaults::default());
+//! for remote in &layered.config.remotes {
+//!     println!("{} -> {}", remote.name, remote.build_url("my-repo", "my-repo")?);
+//! }
+//!
+//! let git = ProcessGit::new();
+//! let urls = git.remote_push_urls(REMOTE_NAME)?;
+//! let engine = PushEngine::plan(
+//!     &layered.config,
+//!     &defaults,
+//!     urls,
+//!     &git.current_branch()?,
+//!     &PushRequest::default(),
+//! )?;
+//! let report = engine.run(&git, &());
+//! for outcome in &report.outcomes {
+//!     println!("{}: {:?} ({} 次)", outcome.name, outcome.status, outcome.attempts);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub mod config;
+pub mod engine;
+pub mod git;
+pub mod layout;
+pub mod proxy;
+pub mod rename;
+pub mod secret;
+pub mod snapshot;
+pub mod template;
+pub mod utils;
+
+pub use config::{load_layered_config, Config, Defaults, Remote, ResolvedDefaults};
+pub use engine::{PushEngine, PushOutcome, PushReport, PushRequest, PushStatus};
+pub use layout::{Layout, ManagedRemotes};
+pub use utils::build_remote_url;
+
+/// 本工具在 git 仓库中管理的统一远程仓库名称
+pub const REMOTE_NAME: &str = "yewpb";
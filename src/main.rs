@@ -1,13 +1,13 @@
 mod cli;
 mod commands;
-mod config;
-mod git;
-mod utils;
 
 use anyhow::Result;
 use clap::Parser;
 use dotenvy::dotenv;
-use git::{PushOptions, RetryConfig};
+use std::num::NonZeroUsize;
+use yewpb::config;
+use yewpb::engine::{PushFilter, PushRequest, RetryArgs};
+use yewpb::git::{ProcessGit, PushOptions};
 
 fn main() -> Result<()> {
     // 加载 .env，便于本地开发配置环境变量
@@ -15,27 +15,66 @@ fn main() -> Result<()> {
     // 命令入口，负责分发子命令并执行核心逻辑
     let cli = cli::Cli::parse();
     let config_path = config::config_path()?;
+    let git = ProcessGit::new();
 
     match cli.command {
-        cli::Commands::Config { command } => commands::config(&config_path, command),
+        cli::Commands::Config { command } => commands::config(&git, &config_path, command),
         cli::Commands::Apply {
             repo,
             yes,
             timeout,
             dry_run,
             no_hook,
+            group,
+            layout,
+            prune,
+            recursive,
+            include,
+            exclude,
+            max_depth,
+            jobs,
         } => {
-            let cfg = config::load_config(&config_path)?;
-            let timeout = timeout
-                .or(cfg.defaults.check_timeout)
-                .unwrap_or(config::DEFAULT_CHECK_TIMEOUT);
-            commands::apply(&config_path, repo, yes, timeout, dry_run, no_hook)
+            // 超时与分组在读取配置后解析，批量模式下使用各仓库自己的配置
+            let args = commands::apply::ApplyArgs {
+                repo,
+                yes,
+                timeout,
+                dry_run,
+                no_hook,
+                group,
+                layout,
+                prune,
+                jobs: jobs.map(NonZeroUsize::get),
+            };
+            match recursive {
+                Some(root) => commands::apply_recursive(
+                    &config_path,
+                    args,
+                    commands::apply_recursive::ScanArgs {
+                        root,
+                        include,
+                        exclude,
+                        max_depth,
+                    },
+                ),
+                None => {
+                    commands::apply(&git, &config_path, &config::ConfigSources::current(), args)
+                }
+            }
         }
-        cli::Commands::Clean { dry_run } => commands::clean(dry_run),
+        cli::Commands::Clean { dry_run, restore } => commands::clean(
+            &git,
+            &config_path,
+            &config::ConfigSources::current(),
+            dry_run,
+            restore,
+        ),
+        cli::Commands::Restore { snapshot, list } => commands::restore(&git, list, snapshot),
         cli::Commands::Push {
             dry_run,
             only,
             except,
+            group,
             force,
             force_with_lease,
             set_upstream,
@@ -60,44 +99,48 @@ fn main() -> Result<()> {
                 extra_args,
             };
 
-            let cfg = config::load_config(&config_path)?;
-            let retry_config = RetryConfig {
-                max_retries: retry
-                    .or(cfg.defaults.retry)
-                    .unwrap_or(config::DEFAULT_RETRY),
-                delay_ms: retry_delay
-                    .or(cfg.defaults.retry_delay)
-                    .unwrap_or(config::DEFAULT_RETRY_DELAY),
-                timeout_secs: timeout
-                    .or(cfg.defaults.timeout)
-                    .unwrap_or(config::DEFAULT_PUSH_TIMEOUT),
+            let resolved = config::load_layered_config(&config_path)?.resolve(&config::Defaults {
+                group,
+                ..Default::default()
+            });
+            // 重试参数按远程仓库分别解析，这里只传递命令行指定的值
+            let request = PushRequest {
+                filter: PushFilter {
+                    only,
+                    except,
+                    group: resolved.group,
+                },
+                options,
+                retry: RetryArgs {
+                    retry,
+                    retry_delay,
+                    timeout,
+                },
+                skip_check,
             };
 
-            commands::push(
+            commands::push(&git, &config_path, dry_run, &request)
+        }
+        cli::Commands::Status => commands::status(&git, &config_path),
+        cli::Commands::Check { timeout, jobs } => {
+            let resolved = config::load_layered_config(&config_path)?.resolve(&config::Defaults {
+                check_timeout: timeout,
+                ..Default::default()
+            });
+            commands::check(
+                &git,
                 &config_path,
-                dry_run,
-                only,
-                except,
-                &options,
-                &retry_config,
-                skip_check,
+                resolved.check_timeout,
+                jobs.map(NonZeroUsize::get),
             )
         }
-        cli::Commands::Status => commands::status(&config_path),
-        cli::Commands::Check { timeout } => {
-            let cfg = config::load_config(&config_path)?;
-            let timeout = timeout
-                .or(cfg.defaults.check_timeout)
-                .unwrap_or(config::DEFAULT_CHECK_TIMEOUT);
-            commands::check(&config_path, timeout)
-        }
         cli::Commands::Alias { name, remove, show } => {
-            commands::alias(&config_path, name, remove, show)
+            commands::alias(&git, &config_path, name, remove, show)
         }
         cli::Commands::Hook { command } => match command {
-            cli::HookCommands::Install { yes } => commands::hook::execute_install(yes),
-            cli::HookCommands::Uninstall { yes } => commands::hook::execute_uninstall(yes),
-            cli::HookCommands::Status => commands::hook::execute_status(),
+            cli::HookCommands::Install { yes } => commands::hook::execute_install(&git, yes),
+            cli::HookCommands::Uninstall { yes } => commands::hook::execute_uninstall(&git, yes),
+            cli::HookCommands::Status => commands::hook::execute_status(&git),
         },
         cli::Commands::Markdown => {
             commands::markdown();
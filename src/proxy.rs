@@ -0,0 +1,201 @@
+use anyhow::{bail, Result};
+
+/// 支持的代理协议
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks4", "socks5", "socks5h"];
+
+/// 远程地址使用的传输方式，决定代理的注入方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// http(s)://，通过 git -c http.proxy 设置
+    Http,
+    /// ssh:// 或 scp 风格地址，通过 ssh 的 ProxyCommand 设置
+    Ssh,
+    /// file:// 或本地路径等，不经过代理
+    Local,
+}
+
+impl Transport {
+    pub fn of(url: &str) -> Self {
+        match url.split_once("://") {
+            Some(("http" | "https", _)) => Transport::Http,
+            Some(("ssh" | "git+ssh" | "ssh+git", _)) => Transport::Ssh,
+            Some(_) => Transport::Local,
+            // scp 风格: [user@]host:path，冒号前不能包含 /
+            None => match url.split_once(':') {
+                Some((host, _)) if !host.is_empty() && !host.contains('/') => Transport::Ssh,
+                _ => Transport::Local,
+            },
+        }
+    }
+}
+
+/// 远程仓库最终使用的代理
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxySetting {
+    /// 使用指定的代理地址
+    Use(String),
+    /// 明确不使用代理，忽略 git 配置与环境变量中的代理
+    Bypass,
+    /// 未配置，沿用 git 与 ssh 自身的行为
+    Inherit,
+}
+
+/// 校验代理地址，形如 http://host:port 或 socks5://host:port
+pub fn validate(proxy: &str) -> Result<()> {
+    let Some((scheme, rest)) = proxy.split_once("://") else {
+        bail!("代理地址 '{}' 缺少协议，应为 http://host:port 形式", proxy);
+    };
+    if !PROXY_SCHEMES.contains(&scheme) {
+        bail!(
+            "不支持的代理协议 '{}'，可用协议: {}",
+            scheme,
+            PROXY_SCHEMES.join(", ")
+        );
+    }
+    if authority(rest).is_empty() || proxy.chars().any(char::is_whitespace) {
+        bail!("代理地址 '{}' 缺少主机名", proxy);
+    }
+    Ok(())
+}
+
+/// 代理地址是否包含认证信息，ssh 的 ProxyCommand 无法使用
+pub fn has_credentials(proxy: &str) -> bool {
+    proxy
+        .split_once("://")
+        .is_some_and(|(_, rest)| rest.split('/').next().unwrap_or(rest).contains('@'))
+}
+
+/// 生成 ssh 的 ProxyCommand，使用 nc 通过 HTTP CONNECT 或 SOCKS 代理连接
+pub fn ssh_proxy_command(proxy: &str) -> String {
+    let (scheme, rest) = proxy.split_once("://").unwrap_or(("http", proxy));
+    let address = authority(rest);
+    let protocol = match scheme {
+        "socks4" => "4",
+        "socks5" | "socks5h" => "5",
+        _ => "connect",
+    };
+    format!("nc -X {} -x {} %h %p", protocol, address)
+}
+
+/// 提取远程地址中的主机名，用于匹配 no_proxy
+pub fn host_of(url: &str) -> Option<&str> {
+    let authority = match url.split_once("://") {
+        Some((_, rest)) => authority(rest),
+        None => url.split_once(':')?.0.rsplit('@').next()?,
+    };
+    // 去掉端口，IPv6 地址保留方括号内的部分
+    let host = match authority.strip_prefix('[') {
+        Some(rest) => rest.split(']').next()?,
+        None => authority.split(':').next()?,
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// 判断主机是否在逗号分隔的 no_proxy 列表中，`*` 匹配所有主机
+///
+/// 条目匹配主机本身及其子域名，条目中的端口会被忽略
+pub fn matches_no_proxy(host: &str, no_proxy: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    no_proxy
+        .split(',')
+        .map(|entry| no_proxy_host(entry.trim()).to_ascii_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            entry == "*"
+                || host == entry
+                || host
+                    .strip_suffix(&entry)
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+}
+
+/// no_proxy 条目中的主机名：去掉开头的 `.`、端口与 IPv6 地址的方括号
+fn no_proxy_host(entry: &str) -> &str {
+    let entry = entry.trim_start_matches('.');
+    if let Some(rest) = entry.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or(rest);
+    }
+    match entry.rsplit_once(':') {
+        // 不带方括号的 IPv6 地址包含多个冒号，没有端口
+        Some((host, port)) if !host.contains(':') && port.chars().all(|c| c.is_ascii_digit()) => {
+            host
+        }
+        _ => entry,
+    }
+}
+
+/// 去掉用户信息与路径，只保留 host[:port]
+fn authority(rest: &str) -> &str {
+    let authority = rest.split('/').next().unwrap_or(rest);
+    authority.rsplit('@').next().unwrap_or(authority)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_ssh_proxy_command() {
+        assert_eq!(
+            ssh_proxy_command("http://proxy.local:3128"),
+            "nc -X connect -x proxy.local:3128 %h %p"
+        );
+        assert_eq!(
+            ssh_proxy_command("socks5h://proxy.local:1080/"),
+            "nc -X 5 -x proxy.local:1080 %h %p"
+        );
+        assert_eq!(
+            ssh_proxy_command("socks4://proxy.local:1080"),
+            "nc -X 4 -x proxy.local:1080 %h %p"
+        );
+        assert_eq!(
+            ssh_proxy_command("proxy.local:3128"),
+            "nc -X connect -x proxy.local:3128 %h %p"
+        );
+    }
+
+    #[test]
+    fn extracts_host_from_remote_urls() {
+        assert_eq!(
+            host_of("https://user:pw@GitHub.com:443/me/x.git"),
+            Some("GitHub.com")
+        );
+        assert_eq!(
+            host_of("ssh://git@gitlab.internal:2222/me/x.git"),
+            Some("gitlab.internal")
+        );
+        assert_eq!(host_of("git@gitee.com:me/x.git"), Some("gitee.com"));
+        assert_eq!(host_of("ssh://git@[::1]:22/x.git"), Some("::1"));
+        assert_eq!(host_of("/srv/git/x.git"), None);
+        assert_eq!(host_of("file:///srv/git/x.git"), None);
+    }
+
+    #[test]
+    fn matches_no_proxy_entries() {
+        let no_proxy = "localhost, .corp.example.com,gitlab.internal:8443,[::1],10.0.0.1";
+        assert!(matches_no_proxy("localhost", no_proxy));
+        assert!(matches_no_proxy("corp.example.com", no_proxy));
+        assert!(matches_no_proxy("git.corp.example.com", no_proxy));
+        assert!(matches_no_proxy("GitLab.Internal", no_proxy));
+        assert!(matches_no_proxy("::1", no_proxy));
+        assert!(matches_no_proxy("10.0.0.1", no_proxy));
+
+        // 只匹配完整的域名段
+        assert!(!matches_no_proxy("evilcorp.example.com", no_proxy));
+        assert!(!matches_no_proxy("example.com", no_proxy));
+        assert!(!matches_no_proxy("notlocalhost", no_proxy));
+        assert!(!matches_no_proxy("10.0.0.10", no_proxy));
+
+        assert!(matches_no_proxy("anything", "*"));
+        assert!(!matches_no_proxy("anything", " , "));
+    }
+
+    #[test]
+    fn detects_transport() {
+        assert_eq!(Transport::of("https://h/x.git"), Transport::Http);
+        assert_eq!(Transport::of("ssh://git@h/x.git"), Transport::Ssh);
+        assert_eq!(Transport::of("git@h:x.git"), Transport::Ssh);
+        assert_eq!(Transport::of("file:///srv/x.git"), Transport::Local);
+        assert_eq!(Transport::of("./a:b/x.git"), Transport::Local);
+    }
+}
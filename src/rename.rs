@@ -0,0 +1,177 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 仓库名称的大小写转换方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum NameCase {
+    /// 全部小写
+    Lower,
+    /// 全部大写
+    Upper,
+    /// 单词以 - 连接并小写
+    Kebab,
+    /// 单词以 _ 连接并小写
+    Snake,
+}
+
+/// 单个远程仓库的仓库名称映射规则
+///
+/// 处理顺序：固定名称优先；否则依次应用正则替换、大小写转换、前缀与后缀
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RenameRule {
+    /// 固定使用的仓库名称，设置后忽略其他规则
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 添加的前缀
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// 添加的后缀
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    /// 大小写转换
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case: Option<NameCase>,
+    /// 正则表达式，匹配部分替换为 replace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// 正则替换内容，支持 $1 等捕获组引用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace: Option<String>,
+}
+
+impl RenameRule {
+    /// 是否没有设置任何规则
+    pub fn is_empty(&self) -> bool {
+        *self == RenameRule::default()
+    }
+
+    /// 校验规则中的正则表达式，设置了固定名称时同样校验
+    pub fn validate(&self) -> Result<()> {
+        self.regex().map(|_| ())
+    }
+
+    fn regex(&self) -> Result<Option<Regex>> {
+        self.pattern
+            .as_deref()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .with_context(|| format!("仓库名称替换规则中的正则表达式无效: {}", pattern))
+            })
+            .transpose()
+    }
+
+    /// 将规则应用到仓库名称上
+    pub fn apply(&self, repo: &str) -> Result<String> {
+        if let Some(name) = &self.name {
+            return Ok(name.clone());
+        }
+
+        let mut result = repo.to_string();
+        if let Some(regex) = self.regex()? {
+            let replace = self.replace.as_deref().unwrap_or("");
+            result = regex.replace_all(&result, replace).into_owned();
+        }
+        if let Some(case) = self.case {
+            result = convert_case(&result, case);
+        }
+        if let Some(prefix) = &self.prefix {
+            result.insert_str(0, prefix);
+        }
+        if let Some(suffix) = &self.suffix {
+            result.push_str(suffix);
+        }
+        Ok(result)
+    }
+}
+
+/// 转换大小写，kebab/snake 会按 -、_、空格和驼峰边界拆分单词
+fn convert_case(name: &str, case: NameCase) -> String {
+    match case {
+        NameCase::Lower => name.to_lowercase(),
+        NameCase::Upper => name.to_uppercase(),
+        NameCase::Kebab => split_words(name).join("-"),
+        NameCase::Snake => split_words(name).join("_"),
+    }
+}
+
+/// 拆分单词并转为小写
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c == '-' || c == '_' || c.is_whitespace() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_rules_in_order() {
+        let rule = RenameRule {
+            pattern: Some("^yew-(.*)$".to_string()),
+            replace: Some("$1".to_string()),
+            case: Some(NameCase::Kebab),
+            prefix: Some("mirror-".to_string()),
+            suffix: Some("-bak".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            rule.apply("yew-PollenBreeze").unwrap(),
+            "mirror-pollen-breeze-bak"
+        );
+        assert_eq!(RenameRule::default().apply("demo").unwrap(), "demo");
+    }
+
+    #[test]
+    fn fixed_name_ignores_other_rules() {
+        let rule = RenameRule {
+            name: Some("other".to_string()),
+            suffix: Some("-bak".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(rule.apply("demo").unwrap(), "other");
+    }
+
+    #[test]
+    fn converts_case() {
+        assert_eq!(convert_case("myRepo_Name", NameCase::Kebab), "my-repo-name");
+        assert_eq!(
+            convert_case("my-repo name", NameCase::Snake),
+            "my_repo_name"
+        );
+        assert_eq!(convert_case("MyRepo", NameCase::Upper), "MYREPO");
+        assert_eq!(convert_case("MyRepo", NameCase::Lower), "myrepo");
+    }
+
+    #[test]
+    fn invalid_pattern_is_rejected_even_with_fixed_name() {
+        let rule = RenameRule {
+            name: Some("other".to_string()),
+            pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(rule.apply("demo").is_ok());
+        let err = rule.validate().unwrap_err();
+        assert!(err.to_string().contains("正则表达式无效"));
+    }
+}
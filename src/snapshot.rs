@@ -0,0 +1,281 @@
+//! 仓库远程仓库配置的快照
+//!
+//! `apply` 与 `clean` 修改远程仓库前会把所有 `remote.*` 配置保存到 `.git/yewpb/` 中，
+//! `restore` 可以按快照原样重建这些远程仓库。
+
+use crate::config::write_atomic;
+use crate::git::GitBackend;
+use crate::layout::MANAGED_PUSH_URLS;
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// 最多保留的快照数量，超出后删除较旧的快照，但最早的快照始终保留
+pub const MAX_SNAPSHOTS: usize = 20;
+
+/// 快照文件名前缀
+const SNAPSHOT_PREFIX: &str = "remotes-";
+/// 快照编号中的时间格式，使用 UTC 时间，保证编号随时间递增
+const ID_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// 单个远程仓库的全部配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteSnapshot {
+    pub name: String,
+    /// `remote.<name>.` 之后的配置项及其值，按配置文件中的顺序排列
+    pub settings: Vec<(String, String)>,
+}
+
+/// 某一时刻仓库中所有远程仓库的配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// 创建快照的命令
+    pub command: String,
+    /// 当时记录的 yewpb 推送地址
+    #[serde(default)]
+    pub managed: Vec<String>,
+    #[serde(default)]
+    pub remotes: Vec<RemoteSnapshot>,
+}
+
+impl Snapshot {
+    /// 读取仓库当前的远程仓库配置
+    pub fn capture(git: &dyn GitBackend, command: &str) -> Result<Self> {
+        let mut remotes: Vec<RemoteSnapshot> = Vec::new();
+        for (key, value) in git.repo_config_list()? {
+            // 远程仓库名称中可以包含 '.'，配置项名称不能
+            let Some((name, var)) = key
+                .strip_prefix("remote.")
+                .and_then(|rest| rest.rsplit_once('.'))
+            else {
+                continue;
+            };
+            match remotes.iter_mut().find(|remote| remote.name == name) {
+                Some(remote) => remote.settings.push((var.to_string(), value)),
+                None => remotes.push(RemoteSnapshot {
+                    name: name.to_string(),
+                    settings: vec![(var.to_string(), value)],
+                }),
+            }
+        }
+        Ok(Snapshot {
+            command: command.to_string(),
+            managed: git.repo_config_get_all(MANAGED_PUSH_URLS)?,
+            remotes,
+        })
+    }
+
+    /// 删除仓库当前的所有远程仓库配置，再按快照重建
+    ///
+    /// 只修改配置，不删除远程跟踪分支
+    pub fn restore(&self, git: &dyn GitBackend) -> Result<()> {
+        let mut names: Vec<String> = git.remote_names()?.into_iter().collect();
+        names.sort();
+        for name in names {
+            git.repo_config_remove_section(&format!("remote.{}", name))?;
+        }
+        git.repo_config_unset(MANAGED_PUSH_URLS, None)?;
+
+        for remote in &self.remotes {
+            for (var, value) in &remote.settings {
+                git.repo_config_add(&format!("remote.{}.{}", remote.name, var), value)?;
+            }
+        }
+        for url in &self.managed {
+            git.repo_config_add(MANAGED_PUSH_URLS, url)?;
+        }
+        Ok(())
+    }
+
+    /// 快照中的远程仓库名称
+    pub fn remote_names(&self) -> Vec<&str> {
+        self.remotes
+            .iter()
+            .map(|remote| remote.name.as_str())
+            .collect()
+    }
+}
+
+/// 保存在 `.git/yewpb/` 中的一个快照文件
+pub struct SnapshotFile {
+    /// 快照编号，即创建时间，同一秒内的多个快照追加序号
+    pub id: String,
+    pub path: PathBuf,
+}
+
+impl SnapshotFile {
+    /// 快照的创建时间，转换为本地时间用于展示
+    pub fn time(&self) -> String {
+        let stamp = self.id.get(..15).unwrap_or(&self.id);
+        match NaiveDateTime::parse_from_str(stamp, ID_FORMAT) {
+            Ok(time) => time
+                .and_utc()
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            Err(_) => self.id.clone(),
+        }
+    }
+
+    /// 读取快照内容
+    pub fn load(&self) -> Result<Snapshot> {
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("读取快照失败: {}", self.path.display()))?;
+        toml::from_str(&content).with_context(|| format!("解析快照失败: {}", self.path.display()))
+    }
+}
+
+/// 快照目录，位于仓库的 .git/yewpb
+pub fn snapshot_dir(git: &dyn GitBackend) -> Result<PathBuf> {
+    Ok(git.git_dir()?.join("yewpb"))
+}
+
+/// 按时间从新到旧列出所有快照
+pub fn list_snapshots(git: &dyn GitBackend) -> Result<Vec<SnapshotFile>> {
+    let dir = snapshot_dir(git)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots: Vec<SnapshotFile> = fs::read_dir(&dir)
+        .with_context(|| format!("读取快照目录失败: {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let id = name.strip_prefix(SNAPSHOT_PREFIX)?.strip_suffix(".toml")?;
+            Some(SnapshotFile {
+                id: id.to_string(),
+                path: entry.path(),
+            })
+        })
+        .collect();
+    // 编号以时间开头，同一秒内的序号递增，按 (时间, 序号) 排序
+    snapshots.sort_by_key(|s| std::cmp::Reverse(sort_key(&s.id)));
+    Ok(snapshots)
+}
+
+fn sort_key(id: &str) -> (String, u32) {
+    match id.get(15..).and_then(|s| s.strip_prefix('-')) {
+        Some(seq) => (id[..15].to_string(), seq.parse().unwrap_or(0)),
+        None => (id.to_string(), 0),
+    }
+}
+
+/// 按编号查找快照，支持唯一的编号前缀
+pub fn find_snapshot(git: &dyn GitBackend, id: &str) -> Result<SnapshotFile> {
+    let mut matches: Vec<SnapshotFile> = list_snapshots(git)?
+        .into_iter()
+        .filter(|s| s.id.starts_with(id))
+        .collect();
+    match matches.len() {
+        0 => bail!(
+            "未找到快照 '{}'，运行 yewpb restore --list 查看所有快照",
+            id
+        ),
+        1 => Ok(matches.remove(0)),
+        _ => match matches.iter().position(|s| s.id == id) {
+            Some(i) => Ok(matches.remove(i)),
+            None => bail!(
+                "快照编号 '{}' 不唯一，匹配到: {}",
+                id,
+                matches
+                    .iter()
+                    .map(|s| s.id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        },
+    }
+}
+
+/// 保存当前的远程仓库配置，并清理超出数量的旧快照
+pub fn save_snapshot(git: &dyn GitBackend, command: &str) -> Result<SnapshotFile> {
+    let snapshot = Snapshot::capture(git, command)?;
+    let dir = snapshot_dir(git)?;
+    fs::create_dir_all(&dir).with_context(|| format!("创建快照目录失败: {}", dir.display()))?;
+
+    // 同一秒内的序号接着最新的快照递增，不复用已清理的旧编号
+    let stamp = Utc::now().format(ID_FORMAT).to_string();
+    let id = match list_snapshots(git)?
+        .first()
+        .map(|latest| sort_key(&latest.id))
+    {
+        Some((time, seq)) if time == stamp => format!("{}-{}", stamp, seq + 1),
+        _ => stamp,
+    };
+    let path = dir.join(snapshot_file_name(&id));
+    let content = toml::to_string(&snapshot).context("序列化快照失败")?;
+    write_atomic(&path, &content)?;
+
+    // 最早的快照是 yewpb 修改仓库之前的状态，clean --restore 依赖它
+    let mut snapshots = list_snapshots(git)?;
+    snapshots.pop();
+    for old in snapshots.into_iter().skip(MAX_SNAPSHOTS - 1) {
+        let _ = fs::remove_file(&old.path);
+    }
+    Ok(SnapshotFile { id, path })
+}
+
+fn snapshot_file_name(id: &str) -> String {
+    format!("{}{}.toml", SNAPSHOT_PREFIX, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::fixtures::A;
+    use crate::git::FakeGit;
+
+    #[test]
+    fn restores_remotes_and_settings_exactly() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new()
+            .with_git_dir(dir.path())
+            .with_remote("origin", "https://origin.example.com/demo.git")
+            .with_repo_config("remote.origin.fetch", "+refs/heads/*:refs/remotes/origin/*")
+            .with_remote("gitlab", "https://gitlab.com/me/demo.git")
+            .with_push_url("gitlab", "https://gitlab.com/me/demo-push.git")
+            .with_repo_config("remote.gitlab.tagopt", "--no-tags")
+            .with_repo_config("core.bare", "false");
+        let remote_entries = |git: &FakeGit| -> Vec<(String, String)> {
+            git.repo_config_list()
+                .unwrap()
+                .into_iter()
+                .filter(|(key, _)| key.starts_with("remote."))
+                .collect()
+        };
+        let before = remote_entries(&git);
+
+        let file = save_snapshot(&git, "apply").unwrap();
+        git.remove_remote("gitlab").unwrap();
+        git.add_remote("yewpb", A).unwrap();
+        git.repo_config_add(MANAGED_PUSH_URLS, A).unwrap();
+
+        file.load().unwrap().restore(&git).unwrap();
+
+        // 远程仓库按原来的顺序重建，每个远程仓库内的配置项顺序也不变
+        assert_eq!(remote_entries(&git), before);
+        assert_eq!(git.repo_config("core.bare"), vec!["false"]);
+        assert!(git.repo_config(MANAGED_PUSH_URLS).is_empty());
+    }
+
+    #[test]
+    fn lists_newest_first_and_keeps_the_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = FakeGit::new().with_git_dir(dir.path());
+
+        let first = save_snapshot(&git, "apply").unwrap();
+        let mut last = None;
+        for _ in 0..MAX_SNAPSHOTS + 5 {
+            last = Some(save_snapshot(&git, "apply").unwrap());
+        }
+
+        let snapshots = list_snapshots(&git).unwrap();
+        assert_eq!(snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(snapshots[0].id, last.unwrap().id);
+        assert_eq!(snapshots.last().unwrap().id, first.id);
+        assert_eq!(find_snapshot(&git, &first.id).unwrap().id, first.id);
+        assert!(find_snapshot(&git, "1999").is_err());
+    }
+}
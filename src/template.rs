@@ -0,0 +1,319 @@
+use anyhow::{bail, Result};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// 内置占位符，由本工具在生成地址时提供
+pub const BUILTIN_PLACEHOLDERS: &[&str] = &["repo", "dir"];
+
+/// 变量占位符，取值来自远程仓库配置中的 vars 表
+pub const VAR_PLACEHOLDERS: &[&str] = &["owner", "group", "branch_prefix"];
+
+/// 渲染 URL 模板所需的上下文
+pub struct TemplateContext<'a> {
+    /// 仓库名称
+    pub repo: &'a str,
+    /// 当前仓库所在目录名
+    pub dir: &'a str,
+    /// 远程仓库配置中的自定义变量
+    pub vars: &'a BTreeMap<String, String>,
+}
+
+/// 模板解析后的片段
+#[derive(Debug, PartialEq)]
+enum Segment<'a> {
+    Literal(String),
+    Placeholder(&'a str),
+}
+
+/// 解析模板，`{{` 和 `}}` 分别表示字面量 `{` 和 `}`
+///
+/// `${VAR}` 是推送时才展开的环境变量，原样保留在生成的地址中
+fn parse(template: &str) -> Result<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = template;
+
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '$' if rest.starts_with("${") => {
+                let end = match rest.find('}') {
+                    Some(end) => end,
+                    None => bail!("模板中的 '${{' 未闭合: {}", template),
+                };
+                literal.push_str(&rest[..=end]);
+                rest = &rest[end + 1..];
+            }
+            '{' if rest.starts_with("{{") => {
+                literal.push('{');
+                rest = &rest[2..];
+            }
+            '}' if rest.starts_with("}}") => {
+                literal.push('}');
+                rest = &rest[2..];
+            }
+            '{' => {
+                let end = match rest.find('}') {
+                    Some(end) => end,
+                    None => bail!("模板中的 '{{' 未闭合: {}", template),
+                };
+                let name = &rest[1..end];
+                if name.is_empty() {
+                    bail!("模板中存在空占位符 '{{}}': {}", template);
+                }
+                if name.contains('{') {
+                    bail!("模板中的 '{{' 未闭合: {}", template);
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Placeholder(name));
+                rest = &rest[end + 1..];
+            }
+            '}' => bail!("模板中存在多余的 '}}'，字面量请写作 '}}}}': {}", template),
+            _ => {
+                literal.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+/// 校验模板语法与占位符名称
+pub fn validate(template: &str) -> Result<()> {
+    let segments = parse(template)?;
+    for segment in &segments {
+        if let Segment::Placeholder(name) = segment {
+            if !BUILTIN_PLACEHOLDERS.contains(name) && !VAR_PLACEHOLDERS.contains(name) {
+                bail!(
+                    "未知的模板占位符 '{{{}}}'，可用占位符: {}",
+                    name,
+                    BUILTIN_PLACEHOLDERS
+                        .iter()
+                        .chain(VAR_PLACEHOLDERS)
+                        .map(|p| format!("{{{}}}", p))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+        }
+    }
+    if !segments.contains(&Segment::Placeholder("repo")) {
+        bail!("模板必须包含 {{repo}} 占位符: {}", template);
+    }
+    // 开头就是随仓库变化的占位符时，任何以相同后缀结尾的地址都会匹配到该配置
+    if let Some(Segment::Placeholder(name)) = segments.first() {
+        if BUILTIN_PLACEHOLDERS.contains(name) {
+            bail!(
+                "模板不能以 {{{}}} 开头，否则无法将地址匹配回该远程仓库，请以固定地址或 {} 开头: {}",
+                name,
+                VAR_PLACEHOLDERS
+                    .iter()
+                    .map(|p| format!("{{{}}}", p))
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                template
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 使用上下文渲染模板，生成完整的远程地址
+pub fn render(template: &str, ctx: &TemplateContext) -> Result<String> {
+    validate(template)?;
+    let mut url = String::new();
+    for segment in parse(template)? {
+        match segment {
+            Segment::Literal(text) => url.push_str(&text),
+            Segment::Placeholder("repo") => url.push_str(clean_repo_name(ctx.repo)),
+            Segment::Placeholder("dir") => url.push_str(ctx.dir),
+            Segment::Placeholder(name) => match ctx.vars.get(name) {
+                Some(value) => url.push_str(value.trim_matches('/')),
+                None => bail!("模板占位符 '{{{}}}' 未在 vars 中设置", name),
+            },
+        }
+    }
+    Ok(url)
+}
+
+/// 用于匹配的模板片段：字面量与 vars 中的变量是固定文本，其余占位符可以是任意内容
+enum Piece {
+    Fixed(String),
+    Free,
+}
+
+fn pieces(template: &str, vars: &BTreeMap<String, String>) -> Result<Vec<Piece>> {
+    Ok(parse(template)?
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Literal(text) => Piece::Fixed(text),
+            Segment::Placeholder(name) if VAR_PLACEHOLDERS.contains(&name) => {
+                match vars.get(name) {
+                    Some(value) => Piece::Fixed(value.trim_matches('/').to_string()),
+                    None => Piece::Free,
+                }
+            }
+            Segment::Placeholder(_) => Piece::Free,
+        })
+        .collect())
+}
+
+/// 模板中第一个随仓库变化的占位符之前的固定前缀，用于检查地址是否互相包含
+pub fn literal_prefix(template: &str, vars: &BTreeMap<String, String>) -> String {
+    let Ok(pieces) = pieces(template, vars) else {
+        return String::new();
+    };
+    let mut prefix = String::new();
+    for piece in pieces {
+        match piece {
+            Piece::Fixed(text) => prefix.push_str(&text),
+            Piece::Free => break,
+        }
+    }
+    prefix
+}
+
+/// 模板中全部固定文本的长度，匹配多个远程仓库时越长越优先
+pub fn fixed_len(template: &str, vars: &BTreeMap<String, String>) -> usize {
+    pieces(template, vars)
+        .map(|pieces| {
+            pieces
+                .iter()
+                .map(|piece| match piece {
+                    Piece::Fixed(text) => text.len(),
+                    Piece::Free => 0,
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// 判断 URL 是否可能由该模板生成：固定文本原样匹配，每个其余占位符匹配任意非空内容
+pub fn matches(template: &str, vars: &BTreeMap<String, String>, url: &str) -> bool {
+    let Ok(pieces) = pieces(template, vars) else {
+        return false;
+    };
+    if !pieces
+        .iter()
+        .any(|piece| matches!(piece, Piece::Fixed(text) if !text.is_empty()))
+    {
+        return false;
+    }
+    let mut pattern = String::from("^");
+    for piece in &pieces {
+        match piece {
+            Piece::Fixed(text) => pattern.push_str(&regex::escape(text)),
+            Piece::Free => pattern.push_str(".+?"),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).is_ok_and(|re| re.is_match(url))
+}
+
+/// 清理仓库名：去除首尾斜杠和 .git 后缀
+fn clean_repo_name(repo: &str) -> &str {
+    let repo = repo.trim().trim_start_matches('/').trim_end_matches('/');
+    repo.strip_suffix(".git").unwrap_or(repo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn render_with(template: &str, vars: &BTreeMap<String, String>) -> Result<String> {
+        render(
+            template,
+            &TemplateContext {
+                repo: "demo.git",
+                dir: "work",
+                vars,
+            },
+        )
+    }
+
+    #[test]
+    fn parses_placeholders_escapes_and_env_vars() {
+        let segments = parse("https://${TOKEN}@h/{{x}}/{owner}/{repo}").unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Literal("https://${TOKEN}@h/{x}/".to_string()),
+                Segment::Placeholder("owner"),
+                Segment::Literal("/".to_string()),
+                Segment::Placeholder("repo"),
+            ]
+        );
+        assert!(parse("https://h/{repo").is_err());
+        assert!(parse("https://h/{}/{repo}").is_err());
+        assert!(parse("https://h/}/{repo}").is_err());
+        assert!(parse("https://h/${TOKEN").is_err());
+    }
+
+    #[test]
+    fn renders_template() {
+        let vars = vars(&[("owner", "/org/"), ("group", "proj")]);
+        let url = render_with(
+            "https://dev.azure.com/{owner}/{group}/_git/{repo}{{1}}",
+            &vars,
+        )
+        .unwrap();
+        assert_eq!(url, "https://dev.azure.com/org/proj/_git/demo{1}");
+        assert_eq!(
+            render_with("git@h:{dir}/{repo}.git", &vars).unwrap(),
+            "git@h:work/demo.git"
+        );
+
+        let err = render_with("https://h/{branch_prefix}/{repo}", &vars).unwrap_err();
+        assert!(err.to_string().contains("未在 vars 中设置"));
+        assert!(validate("https://h/{unknown}/{repo}").is_err());
+        assert!(validate("https://h/{owner}").is_err());
+    }
+
+    #[test]
+    fn rejects_templates_starting_with_repo_or_dir() {
+        assert!(validate("{repo}.example.com/x.git").is_err());
+        assert!(validate("{dir}/{repo}").is_err());
+        assert!(validate("{owner}/{repo}.git").is_ok());
+    }
+
+    #[test]
+    fn matches_urls_generated_by_the_template() {
+        let vars = vars(&[("owner", "git@github.com:org")]);
+        let template = "{owner}/{repo}.git";
+        assert_eq!(literal_prefix(template, &vars), "git@github.com:org/");
+        assert!(matches(template, &vars, "git@github.com:org/demo.git"));
+        assert!(matches(template, &vars, "git@github.com:org/a/b.git"));
+        assert!(!matches(template, &vars, "git@github.com:other/demo.git"));
+        assert!(!matches(template, &vars, "git@github.com:org/demo"));
+
+        let azure = "https://dev.azure.com/org/{group}/_git/{repo}";
+        assert_eq!(literal_prefix(azure, &vars), "https://dev.azure.com/org/");
+        assert!(matches(
+            azure,
+            &vars,
+            "https://dev.azure.com/org/p/_git/demo"
+        ));
+        assert!(!matches(azure, &vars, "https://dev.azure.com/org/p/demo"));
+        assert!(!matches(
+            azure,
+            &vars,
+            "https://dev.azure.com/org/_git/demo"
+        ));
+
+        let literal = "https://h/{{x}}/${TOKEN}/{repo}";
+        assert!(matches(literal, &vars, "https://h/{x}/${TOKEN}/demo"));
+        assert!(!matches(literal, &vars, "https://h/x/${TOKEN}/demo"));
+        assert!(!matches("{repo}", &vars, "anything"));
+    }
+}
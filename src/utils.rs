@@ -14,3 +14,46 @@ pub fn build_remote_url(base: &str, repo: &str) -> String {
     }
     url
 }
+
+/// 从远程地址中提取仓库名称（最后一段路径，去掉 .git 后缀）
+pub fn repo_name_from_url(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    url.rsplit(['/', ':'])
+        .next()
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+}
+
+/// 从完整远程地址反推基础地址，是 build_remote_url 的逆操作
+///
+/// 例如 `https://github.com/me/repo.git` -> `https://github.com/me`，
+/// `git@host:repo.git` -> `git@host:`
+pub fn base_from_url(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    // scheme://host/path 形式，至少保留主机名
+    if let Some((scheme, rest)) = url.split_once("://") {
+        let (base, _) = rest.rsplit_once('/')?;
+        return Some(format!("{}://{}", scheme, base));
+    }
+    // scp 风格或本地路径，最后一个 / 或 : 之前为基础地址
+    let index = url.rfind(['/', ':'])?;
+    let base = match &url[index..=index] {
+        ":" => &url[..=index],
+        _ => &url[..index],
+    };
+    (!base.is_empty() && base != ":").then(|| base.to_string())
+}
+
+/// 展开路径开头的 ~ 为用户主目录
+pub fn expand_home(path: &str) -> String {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => rest,
+        _ => return path.to_string(),
+    };
+    match directories::BaseDirs::new() {
+        Some(dirs) => format!("{}{}", dirs.home_dir().display(), rest),
+        None => path.to_string(),
+    }
+}
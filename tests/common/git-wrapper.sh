@@ -0,0 +1,59 @@
+#!/bin/sh
+# 测试用的 git 包装脚本，按环境变量注入故障后再调用真实的 git
+#
+# YEWPB_TEST_HANG=<子命令>      该子命令挂起，用于测试超时
+# YEWPB_TEST_FAIL=<子命令>:<n>  该子命令的前 n 次调用失败，用于测试重试
+# YEWPB_TEST_MATCH=<文本>       只对参数中包含该文本的调用注入故障
+# YEWPB_TEST_STATE=<目录>       保存失败计数与调用日志
+
+# 跳过 -c/-C 及其参数，找到子命令
+subcommand=""
+skip=""
+for arg in "$@"; do
+    if [ -n "$skip" ]; then
+        skip=""
+        continue
+    fi
+    case "$arg" in
+        -c | -C) skip=1 ;;
+        -*) ;;
+        *)
+            subcommand="$arg"
+            break
+            ;;
+    esac
+done
+
+if [ -n "$YEWPB_TEST_STATE" ]; then
+    echo "$*" >>"$YEWPB_TEST_STATE/calls.log"
+fi
+
+matched=1
+if [ -n "$YEWPB_TEST_MATCH" ]; then
+    case "$*" in
+        *"$YEWPB_TEST_MATCH"*) ;;
+        *) matched="" ;;
+    esac
+fi
+
+if [ -n "$matched" ] && [ -n "$subcommand" ]; then
+    if [ "$subcommand" = "$YEWPB_TEST_HANG" ]; then
+        exec sleep 60
+    fi
+
+    case "$YEWPB_TEST_FAIL" in
+        "$subcommand":*)
+            limit="${YEWPB_TEST_FAIL#*:}"
+            counter="$YEWPB_TEST_STATE/$subcommand.failures"
+            count=$(cat "$counter" 2>/dev/null || echo 0)
+            if [ "$count" -lt "$limit" ]; then
+                count=$((count + 1))
+                echo "$count" >"$counter"
+                echo "fatal: injected failure $count/$limit" >&2
+                exit 128
+            fi
+            ;;
+    esac
+fi
+
+exec "$YEWPB_REAL_GIT" "$@"
@@ -0,0 +1,237 @@
+//! 端到端测试的沙箱：临时的工作仓库、本地裸仓库与故障注入用的 git 包装脚本
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+/// 故障注入脚本，安装为沙箱 PATH 中的 `git`
+const GIT_WRAPPER: &str = include_str!("git-wrapper.sh");
+
+/// 工作仓库的默认分支
+pub const BRANCH: &str = "main";
+
+/// 命令执行结果
+pub struct Run {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Run {
+    fn from(output: Output) -> Self {
+        Run {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+    }
+
+    /// 断言命令成功，失败时打印输出
+    #[track_caller]
+    pub fn ok(self) -> Self {
+        assert!(
+            self.success,
+            "命令执行失败\n{}\n{}",
+            self.stdout, self.stderr
+        );
+        self
+    }
+}
+
+/// 一次测试使用的独立环境，HOME、配置文件与 git 全局配置都位于临时目录中
+pub struct Sandbox {
+    root: TempDir,
+    /// 工作仓库
+    work: PathBuf,
+    bin: PathBuf,
+    state: PathBuf,
+    real_git: PathBuf,
+}
+
+impl Sandbox {
+    /// 创建带有一个初始提交的工作仓库
+    pub fn new() -> Self {
+        let root = tempfile::tempdir().expect("创建临时目录失败");
+        let real_git = find_real_git();
+        let sandbox = Sandbox {
+            work: root.path().join("work"),
+            bin: root.path().join("bin"),
+            state: root.path().join("state"),
+            real_git,
+            root,
+        };
+        for dir in [&sandbox.work, &sandbox.bin, &sandbox.state, &sandbox.home()] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        sandbox.install_bin();
+
+        sandbox.git(&["init", "-q", "-b", BRANCH]);
+        sandbox.commit("initial");
+        sandbox
+    }
+
+    /// 创建名为 `<name>/demo.git` 的裸仓库，返回可作为 base 的 `file://` 地址
+    pub fn bare(&self, name: &str) -> String {
+        let dir = self.root.path().join("remotes").join(name);
+        fs::create_dir_all(&dir).unwrap();
+        self.git(&[
+            "init",
+            "-q",
+            "--bare",
+            dir.join("demo.git").to_str().unwrap(),
+        ]);
+        format!("file://{}/", dir.display())
+    }
+
+    /// 写入用户配置文件
+    pub fn write_config(&self, content: &str) {
+        fs::write(self.config_path(), content).unwrap();
+    }
+
+    /// 在工作仓库中新增一个提交，返回 commit hash
+    pub fn commit(&self, message: &str) -> String {
+        fs::write(self.work.join("file.txt"), message).unwrap();
+        self.git(&["add", "file.txt"]);
+        self.git(&["commit", "-q", "-m", message]);
+        self.head()
+    }
+
+    pub fn head(&self) -> String {
+        self.git(&["rev-parse", "HEAD"])
+    }
+
+    /// 裸仓库中分支指向的 commit，分支不存在时返回 None
+    pub fn bare_ref(&self, name: &str, branch: &str) -> Option<String> {
+        let dir = self.root.path().join("remotes").join(name).join("demo.git");
+        let output = self
+            .command(&self.real_git)
+            .args(["-C", dir.to_str().unwrap(), "rev-parse", "--verify", "-q"])
+            .arg(format!("refs/heads/{}", branch))
+            .output()
+            .unwrap();
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// 在工作仓库中执行 git，失败时 panic，返回去掉首尾空白的输出
+    pub fn git(&self, args: &[&str]) -> String {
+        self.git_in(&self.work, args)
+    }
+
+    /// 在指定目录中执行 git
+    pub fn git_in(&self, dir: &Path, args: &[&str]) -> String {
+        let mut cmd = self.command(&self.real_git);
+        cmd.current_dir(dir).args(args);
+        let run = Run::from(cmd.output().unwrap()).ok();
+        run.stdout.trim().to_string()
+    }
+
+    /// 批量测试使用的目录，位于工作仓库之外
+    pub fn tree(&self) -> PathBuf {
+        self.root.path().join("tree")
+    }
+
+    /// 在 tree/<path> 创建一个空仓库，返回其路径
+    pub fn repo(&self, path: &str) -> PathBuf {
+        let dir = self.tree().join(path);
+        fs::create_dir_all(&dir).unwrap();
+        self.git_in(&dir, &["init", "-q", "-b", BRANCH]);
+        dir
+    }
+
+    /// 通过包装脚本执行 git，会触发 hook
+    pub fn git_wrapped(&self, args: &[&str], faults: &[(&str, &str)]) -> Run {
+        let mut cmd = self.command(&self.bin.join("git"));
+        cmd.args(args).envs(faults.iter().copied());
+        Run::from(cmd.output().unwrap())
+    }
+
+    /// 在工作仓库中通过 sh 执行一行命令，用于验证 yewpb 输出的命令可以直接运行
+    pub fn sh(&self, command: &str) -> Run {
+        let mut cmd = self.command(Path::new("sh"));
+        cmd.args(["-c", command]);
+        Run::from(cmd.output().unwrap())
+    }
+
+    /// 在工作仓库中执行 yewpb
+    pub fn yewpb(&self, args: &[&str]) -> Run {
+        self.yewpb_with(args, &[])
+    }
+
+    /// 在工作仓库中执行 yewpb，并通过环境变量注入 git 故障
+    pub fn yewpb_with(&self, args: &[&str], faults: &[(&str, &str)]) -> Run {
+        let mut cmd = self.command(Path::new(env!("CARGO_BIN_EXE_yewpb")));
+        cmd.args(args).envs(faults.iter().copied());
+        Run::from(cmd.output().unwrap())
+    }
+
+    /// 通过包装脚本执行的 git 命令行，按调用顺序排列
+    pub fn git_calls(&self) -> Vec<String> {
+        fs::read_to_string(self.state.join("calls.log"))
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    fn home(&self) -> PathBuf {
+        self.root.path().join("home")
+    }
+
+    pub fn config_path(&self) -> PathBuf {
+        self.root.path().join("config.toml")
+    }
+
+    /// 配置好隔离环境的命令，PATH 中的 git 与 yewpb 指向沙箱中的脚本与被测程序
+    fn command(&self, program: &Path) -> Command {
+        let mut cmd = Command::new(program);
+        for (key, _) in std::env::vars() {
+            if key.starts_with("YEWPB_") || key.starts_with("GIT_") {
+                cmd.env_remove(key);
+            }
+        }
+        let path = std::env::var("PATH").unwrap_or_default();
+        cmd.current_dir(&self.work)
+            .env("PATH", format!("{}:{}", self.bin.display(), path))
+            .env("HOME", self.home())
+            .env("XDG_CONFIG_HOME", self.home().join(".config"))
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_AUTHOR_NAME", "yewpb")
+            .env("GIT_AUTHOR_EMAIL", "yewpb@example.com")
+            .env("GIT_COMMITTER_NAME", "yewpb")
+            .env("GIT_COMMITTER_EMAIL", "yewpb@example.com")
+            .env("YEWPB_CONFIG", self.config_path())
+            .env("YEWPB_SYSTEM_CONFIG", self.root.path().join("system.toml"))
+            .env("YEWPB_REAL_GIT", &self.real_git)
+            .env("YEWPB_TEST_STATE", &self.state);
+        cmd
+    }
+
+    fn install_bin(&self) {
+        let wrapper = self.bin.join("git");
+        fs::write(&wrapper, GIT_WRAPPER).unwrap();
+        make_executable(&wrapper);
+        std::os::unix::fs::symlink(env!("CARGO_BIN_EXE_yewpb"), self.bin.join("yewpb")).unwrap();
+    }
+}
+
+/// 测试进程 PATH 中的 git
+fn find_real_git() -> PathBuf {
+    let output = Command::new("sh")
+        .args(["-c", "command -v git"])
+        .output()
+        .expect("查找 git 失败");
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    assert!(!path.is_empty(), "端到端测试需要安装 git");
+    PathBuf::from(path)
+}
+
+fn make_executable(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = fs::metadata(path).unwrap().permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(path, perms).unwrap();
+}
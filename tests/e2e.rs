@@ -0,0 +1,467 @@
+//! 使用本地裸仓库与 `file://` 地址驱动 yewpb 二进制的端到端测试
+#![cfg(unix)]
+
+mod common;
+
+use common::{Sandbox, BRANCH};
+use std::time::{Duration, Instant};
+
+/// 创建 a、b 两个裸仓库并写入对应配置
+fn sandbox_with_remotes() -> Sandbox {
+    let sandbox = Sandbox::new();
+    let a = sandbox.bare("a");
+    let b = sandbox.bare("b");
+    sandbox.write_config(&format!(
+        r#"
+[defaults]
+retry = 0
+retry_delay = 0
+timeout = 10
+check_timeout = 10
+
+[[remotes]]
+name = "a"
+base = "{a}"
+
+[[remotes]]
+name = "b"
+base = "{b}"
+"#
+    ));
+    sandbox
+}
+
+fn applied() -> Sandbox {
+    let sandbox = sandbox_with_remotes();
+    sandbox.yewpb(&["apply", "demo", "-y", "--no-hook"]).ok();
+    sandbox
+}
+
+fn push_urls(sandbox: &Sandbox) -> Vec<String> {
+    sandbox
+        .git(&["remote", "get-url", "--all", "--push", "yewpb"])
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn apply_configures_unified_remote() {
+    let sandbox = sandbox_with_remotes();
+
+    let run = sandbox.yewpb(&["apply", "demo", "-y", "--no-hook"]).ok();
+
+    let urls = push_urls(&sandbox);
+    assert_eq!(urls.len(), 2);
+    assert!(urls[0].ends_with("/a/demo.git"), "{:?}", urls);
+    assert!(urls[1].ends_with("/b/demo.git"), "{:?}", urls);
+    assert_eq!(sandbox.git(&["remote", "get-url", "yewpb"]), urls[0]);
+    assert_eq!(run.stdout.matches("✓ 可访问").count(), 2, "{}", run.stdout);
+}
+
+#[test]
+fn reapply_only_changes_what_differs() {
+    let sandbox = applied();
+    sandbox.git(&[
+        "config",
+        "--add",
+        "remote.yewpb.fetch",
+        "+refs/tags/*:refs/tags/*",
+    ]);
+    sandbox.git(&["config", "remote.yewpb.tagOpt", "--no-tags"]);
+    sandbox.git(&[
+        "remote",
+        "set-url",
+        "--add",
+        "--push",
+        "yewpb",
+        "file:///srv/manual/demo.git",
+    ]);
+
+    let run = sandbox.yewpb(&["apply", "demo", "-y", "--no-hook"]).ok();
+    assert!(run.stdout.contains("无需修改"), "{}", run.stdout);
+    assert!(!run.stdout.contains("可用性"), "{}", run.stdout);
+
+    // 修改 b 的地址后只替换 b 的推送地址
+    let c = sandbox.bare("c");
+    let config = std::fs::read_to_string(sandbox.config_path()).unwrap();
+    let b = push_urls(&sandbox)[1]
+        .trim_end_matches("demo.git")
+        .to_string();
+    sandbox.write_config(&config.replace(&b, &c));
+    let run = sandbox.yewpb(&["apply", "demo", "-y", "--no-hook"]).ok();
+
+    let urls = push_urls(&sandbox);
+    assert!(urls[0].ends_with("/a/demo.git"), "{:?}", urls);
+    assert_eq!(urls[1], "file:///srv/manual/demo.git");
+    assert!(urls[2].ends_with("/c/demo.git"), "{:?}", urls);
+    assert_eq!(urls.len(), 3);
+    assert_eq!(run.stdout.matches("可用性").count(), 1, "{}", run.stdout);
+    assert_eq!(
+        sandbox.git(&["config", "--get-all", "remote.yewpb.fetch"]),
+        "+refs/heads/*:refs/remotes/yewpb/*\n+refs/tags/*:refs/tags/*"
+    );
+    assert_eq!(sandbox.git(&["config", "remote.yewpb.tagOpt"]), "--no-tags");
+}
+
+#[test]
+fn dry_run_previews_commands_that_actually_run() {
+    let sandbox = applied();
+    let c = sandbox.bare("c");
+    let config = std::fs::read_to_string(sandbox.config_path()).unwrap();
+    let b = push_urls(&sandbox)[1]
+        .trim_end_matches("demo.git")
+        .to_string();
+    sandbox.write_config(&config.replace(&b, &c));
+
+    let run = sandbox.yewpb(&["apply", "demo", "--dry-run"]).ok();
+    let commands: Vec<&str> = run
+        .stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("[dry-run] 将执行: "))
+        .collect();
+    assert_eq!(commands.len(), 2, "{}", run.stdout);
+    assert_eq!(push_urls(&sandbox).len(), 2);
+
+    // 预览的命令粘贴到 shell 中执行，结果与 apply 相同
+    for command in commands {
+        sandbox.sh(command).ok();
+    }
+    let urls = push_urls(&sandbox);
+    assert_eq!(urls.len(), 2, "{:?}", urls);
+    assert!(urls[0].ends_with("/a/demo.git"), "{:?}", urls);
+    assert!(urls[1].ends_with("/c/demo.git"), "{:?}", urls);
+}
+
+#[test]
+fn push_updates_every_bare_repo() {
+    let sandbox = applied();
+    let head = sandbox.head();
+
+    let run = sandbox.yewpb(&["push"]).ok();
+
+    assert!(run.stdout.contains("成功: 2 个"), "{}", run.stdout);
+    assert_eq!(sandbox.bare_ref("a", BRANCH), Some(head.clone()));
+    assert_eq!(sandbox.bare_ref("b", BRANCH), Some(head));
+}
+
+#[test]
+fn push_only_and_except_filter_remotes() {
+    let sandbox = applied();
+    let first = sandbox.head();
+
+    sandbox.yewpb(&["push", "--only", "a"]).ok();
+    assert_eq!(sandbox.bare_ref("a", BRANCH), Some(first.clone()));
+    assert_eq!(sandbox.bare_ref("b", BRANCH), None);
+
+    let second = sandbox.commit("second");
+    sandbox.yewpb(&["push", "--except", "a"]).ok();
+    assert_eq!(sandbox.bare_ref("a", BRANCH), Some(first));
+    assert_eq!(sandbox.bare_ref("b", BRANCH), Some(second));
+}
+
+#[test]
+fn push_retries_injected_failures() {
+    let sandbox = applied();
+    let head = sandbox.head();
+
+    let run = sandbox
+        .yewpb_with(
+            &["push", "--retry", "2", "--skip-check"],
+            &[("YEWPB_TEST_FAIL", "push:2"), ("YEWPB_TEST_MATCH", "/a/")],
+        )
+        .ok();
+
+    assert!(run.stdout.contains("✓ a (尝试 3 次)"), "{}", run.stdout);
+    assert_eq!(sandbox.bare_ref("a", BRANCH), Some(head));
+    let pushes = sandbox
+        .git_calls()
+        .iter()
+        .filter(|call| call.starts_with("push") && call.contains("/a/"))
+        .count();
+    assert_eq!(pushes, 3);
+}
+
+#[test]
+fn push_with_unlimited_timeout_still_checks_reachability() {
+    let sandbox = applied();
+    let head = sandbox.head();
+    // timeout = 0 表示不限制，可用性检查也不能因此立即超时
+    let config = std::fs::read_to_string(sandbox.config_path()).unwrap();
+    sandbox.write_config(&config.replace("name = \"a\"", "name = \"a\"\ntimeout = 0"));
+
+    let run = sandbox.yewpb(&["push"]).ok();
+
+    assert!(run.stdout.contains("成功: 2 个"), "{}", run.stdout);
+    assert_eq!(sandbox.bare_ref("a", BRANCH), Some(head));
+}
+
+#[test]
+fn push_reports_failure_after_retries_are_exhausted() {
+    let sandbox = applied();
+
+    let run = sandbox
+        .yewpb_with(
+            &["push", "--retry", "1", "--skip-check"],
+            &[("YEWPB_TEST_FAIL", "push:5"), ("YEWPB_TEST_MATCH", "/a/")],
+        )
+        .ok();
+
+    assert!(run.stdout.contains("成功: 1 个"), "{}", run.stdout);
+    assert!(run.stdout.contains("✗ a (尝试 2 次)"), "{}", run.stdout);
+    assert!(
+        run.stdout.contains("injected failure 2/5"),
+        "{}",
+        run.stdout
+    );
+    assert_eq!(sandbox.bare_ref("a", BRANCH), None);
+}
+
+#[test]
+fn push_times_out_hanging_remote() {
+    let sandbox = applied();
+    let head = sandbox.head();
+
+    let start = Instant::now();
+    let run = sandbox
+        .yewpb_with(
+            &["push", "--timeout", "1", "--skip-check"],
+            &[("YEWPB_TEST_HANG", "push"), ("YEWPB_TEST_MATCH", "/a/")],
+        )
+        .ok();
+
+    assert!(start.elapsed() < Duration::from_secs(30));
+    assert!(run.stdout.contains("命令超时（1秒）"), "{}", run.stdout);
+    assert_eq!(sandbox.bare_ref("a", BRANCH), None);
+    assert_eq!(sandbox.bare_ref("b", BRANCH), Some(head));
+}
+
+#[test]
+fn status_reports_ahead_commits() {
+    let sandbox = applied();
+    sandbox.yewpb(&["push"]).ok();
+    sandbox.commit("second");
+    sandbox.yewpb(&["push", "--only", "b"]).ok();
+
+    let run = sandbox.yewpb(&["status"]).ok();
+
+    let line = |name: &str| {
+        run.stdout
+            .lines()
+            .find(|line| line.starts_with(&format!("{}:", name)))
+            .unwrap_or_default()
+            .to_string()
+    };
+    assert!(line("a").contains("领先 1 个提交"), "{}", run.stdout);
+    assert!(line("b").contains("已同步"), "{}", run.stdout);
+}
+
+#[test]
+fn check_reports_reachability_and_timeouts() {
+    let sandbox = applied();
+
+    let run = sandbox.yewpb(&["check"]).ok();
+    assert!(
+        run.stdout.contains("检查完成: 2 成功, 0 失败"),
+        "{}",
+        run.stdout
+    );
+
+    let run = sandbox
+        .yewpb_with(
+            &["check", "--timeout", "1"],
+            &[
+                ("YEWPB_TEST_HANG", "ls-remote"),
+                ("YEWPB_TEST_MATCH", "/b/"),
+            ],
+        )
+        .ok();
+    assert!(run.stdout.contains("检查超时（1秒）"), "{}", run.stdout);
+    assert!(
+        run.stdout.contains("检查完成: 1 成功, 1 失败"),
+        "{}",
+        run.stdout
+    );
+}
+
+#[test]
+fn check_runs_remotes_concurrently_in_config_order() {
+    let sandbox = applied();
+    let hang = [("YEWPB_TEST_HANG", "ls-remote")];
+
+    let start = Instant::now();
+    let run = sandbox.yewpb_with(&["check", "--timeout", "2"], &hang).ok();
+    assert!(
+        start.elapsed() < Duration::from_secs(4),
+        "{:?}",
+        start.elapsed()
+    );
+    let a = run.stdout.find("a:").unwrap();
+    let b = run.stdout.find("b:").unwrap();
+    assert!(a < b, "{}", run.stdout);
+    assert!(
+        run.stdout.contains("检查完成: 0 成功, 2 失败"),
+        "{}",
+        run.stdout
+    );
+
+    let start = Instant::now();
+    sandbox
+        .yewpb_with(&["check", "--timeout", "2", "--jobs", "1"], &hang)
+        .ok();
+    assert!(start.elapsed() >= Duration::from_secs(4));
+}
+
+#[test]
+fn clean_removes_unified_remote() {
+    let sandbox = applied();
+
+    sandbox.yewpb(&["clean"]).ok();
+
+    assert!(!sandbox.git(&["remote"]).lines().any(|name| name == "yewpb"));
+}
+
+#[test]
+fn clean_restore_returns_to_remotes_before_apply() {
+    let sandbox = sandbox_with_remotes();
+    let origin = format!("{}demo.git", sandbox.bare("origin"));
+    sandbox.git(&["remote", "add", "origin", &origin]);
+    sandbox.git(&["config", "remote.origin.tagOpt", "--no-tags"]);
+    let before = sandbox.git(&["config", "--local", "--get-regexp", "^remote\\."]);
+
+    sandbox.yewpb(&["apply", "demo", "-y", "--no-hook"]).ok();
+    sandbox.git(&["config", "remote.origin.tagOpt", "--tags"]);
+    let run = sandbox.yewpb(&["restore", "--list"]).ok();
+    assert!(run.stdout.contains("apply    origin"), "{}", run.stdout);
+
+    sandbox.yewpb(&["clean", "--restore"]).ok();
+    assert_eq!(
+        sandbox.git(&["config", "--local", "--get-regexp", "^remote\\."]),
+        before
+    );
+    assert!(!sandbox
+        .git(&["config", "--local", "--list"])
+        .contains("yewpb.pushurl"));
+
+    // 恢复前的状态也保存了快照，可以撤销
+    sandbox.yewpb(&["restore"]).ok();
+    assert_eq!(push_urls(&sandbox).len(), 2);
+    assert_eq!(sandbox.git(&["config", "remote.origin.tagOpt"]), "--tags");
+}
+
+#[test]
+fn recursive_apply_handles_every_repo_in_a_tree() {
+    let sandbox = sandbox_with_remotes();
+    let app = sandbox.repo("app");
+    sandbox.git_in(
+        &app,
+        &["remote", "add", "origin", "https://example.com/me/demo.git"],
+    );
+    let lib = sandbox.repo("libs/lib");
+    sandbox.repo("archive/old");
+    let tree = sandbox.tree();
+    let tree = tree.to_str().unwrap();
+
+    let run = sandbox
+        .yewpb(&["apply", "-r", tree, "--exclude", "archive", "--dry-run"])
+        .ok();
+    assert!(run.stdout.contains("找到 2 个 git 仓库"), "{}", run.stdout);
+    assert!(!run.stdout.contains("archive"), "{}", run.stdout);
+    assert!(run.stdout.contains("将修改 2 个"), "{}", run.stdout);
+    assert_eq!(sandbox.git_in(&app, &["remote"]), "origin");
+
+    let run = sandbox
+        .yewpb(&["apply", "-r", tree, "--exclude", "archive", "--no-hook"])
+        .ok();
+    // 汇总表在每个仓库的变更计划之后
+    let row = |path: &str| {
+        run.stdout
+            .lines()
+            .rfind(|line| line.starts_with(path))
+            .unwrap_or_default()
+            .to_string()
+    };
+    assert!(row("app").contains("demo"), "{}", run.stdout);
+    assert!(row("app").contains("2/2"), "{}", run.stdout);
+    // lib 的裸仓库不存在，地址无法访问
+    assert!(row("libs/lib").contains("0/2"), "{}", run.stdout);
+    let urls = sandbox.git_in(&lib, &["remote", "get-url", "--all", "--push", "yewpb"]);
+    assert!(urls.ends_with("/b/lib.git"), "{}", urls);
+
+    // 未指定 -y 时统一询问一次，默认不安装 hook
+    let run = sandbox
+        .yewpb(&["apply", "-r", tree, "--exclude", "archive"])
+        .ok();
+    assert!(run.stdout.contains("无需修改 2 个"), "{}", run.stdout);
+    assert!(run.stdout.contains("(y/N)"), "{}", run.stdout);
+    assert!(!app.join(".git/hooks/pre-push").exists());
+
+    sandbox
+        .yewpb(&["apply", "-r", tree, "--exclude", "archive", "-y"])
+        .ok();
+    assert!(app.join(".git/hooks/pre-push").exists());
+    assert!(lib.join(".git/hooks/pre-push").exists());
+}
+
+#[test]
+fn recursive_apply_uses_each_repos_check_timeout() {
+    let sandbox = sandbox_with_remotes();
+    let app = sandbox.repo("app");
+    std::fs::write(app.join(".yewpb.toml"), "[defaults]\ncheck_timeout = 1\n").unwrap();
+    let tree = sandbox.tree();
+
+    let run = sandbox
+        .yewpb_with(
+            &["apply", "-r", tree.to_str().unwrap(), "-y", "--no-hook"],
+            &[
+                ("YEWPB_TEST_HANG", "ls-remote"),
+                ("YEWPB_TEST_MATCH", "/a/"),
+            ],
+        )
+        .ok();
+    assert!(run.stdout.contains("检查超时（1秒）"), "{}", run.stdout);
+}
+
+#[test]
+fn hook_syncs_mirrors_on_git_push_origin() {
+    let sandbox = applied();
+    let origin = sandbox.bare("origin");
+    sandbox.git(&["remote", "add", "origin", &format!("{}demo.git", origin)]);
+    sandbox.yewpb(&["hook", "install", "-y"]).ok();
+    let head = sandbox.commit("second");
+
+    let run = sandbox.git_wrapped(&["push", "origin", BRANCH], &[]);
+
+    assert!(run.success, "{}\n{}", run.stdout, run.stderr);
+    assert!(run.stdout.contains("[yewpb]"), "{}", run.stdout);
+    assert_eq!(sandbox.bare_ref("origin", BRANCH), Some(head.clone()));
+    assert_eq!(sandbox.bare_ref("a", BRANCH), Some(head.clone()));
+    assert_eq!(sandbox.bare_ref("b", BRANCH), Some(head));
+}
+
+#[test]
+fn split_layout_supports_fetch_push_status_and_clean() {
+    let sandbox = sandbox_with_remotes();
+    sandbox
+        .yewpb(&["apply", "demo", "-y", "--no-hook", "--layout", "split"])
+        .ok();
+    assert!(!sandbox.git(&["remote"]).lines().any(|name| name == "yewpb"));
+    assert_eq!(
+        sandbox.git(&["config", "remote.a.fetch"]),
+        "+refs/heads/*:refs/remotes/a/*"
+    );
+
+    let head = sandbox.head();
+    sandbox.yewpb(&["push", "--only", "a"]).ok();
+    assert_eq!(sandbox.bare_ref("a", BRANCH), Some(head.clone()));
+    assert_eq!(sandbox.bare_ref("b", BRANCH), None);
+
+    sandbox.git(&["fetch", "-q", "a"]);
+    assert_eq!(sandbox.git(&["rev-parse", "a/main"]), head);
+
+    let run = sandbox.yewpb(&["status"]).ok();
+    assert!(run.stdout.contains("已同步"), "{}", run.stdout);
+
+    sandbox.yewpb(&["clean"]).ok();
+    assert_eq!(sandbox.git(&["remote"]), "");
+}